@@ -1,4 +1,8 @@
-use crate::{op::Operator, ty::Type};
+use crate::{
+    op::Operator,
+    stmt::{Binding, Statement},
+    ty::{Type, TypeExpression},
+};
 use meta::{Span, Spanned};
 
 #[derive(Debug, Clone, PartialEq)]
@@ -32,7 +36,7 @@ pub enum Expr {
     Call {
         callee: Box<Expression>,
         paren: Span,
-        args: Vec<Expression>,
+        args: Vec<Arg>,
     },
     Get {
         object: Box<Expression>,
@@ -53,11 +57,53 @@ pub enum Expr {
         rhs: Box<Expression>,
     },
     List(Vec<Expression>),
+    Tuple(Vec<Expression>),
+    // 'pair.0', the index is checked against the tuple's type while parsing
+    TupleGet {
+        tuple: Box<Expression>,
+        index: Spanned<usize>,
+    },
+    // '...xs' expands a list in place, so it only appears as a call argument or list element
+    Spread(Box<Expression>),
     ListShorthand {
         value: Box<Expression>,
         count: Box<Expression>,
     },
+    // '[x * 2 for x in xs if x > 0]', the binding is only in scope for the value and condition
+    ListComprehension {
+        value: Box<Expression>,
+        binding: Binding,
+        iterable: Box<Expression>,
+        cond: Option<Box<Expression>>,
+    },
     This,
+    // Tests the type of a value at runtime. Ex: 'x is int'
+    Is {
+        expr: Box<Expression>,
+        ty: TypeExpression,
+    },
+    // Checks the type of a value at runtime, failing if it doesn't match. Ex: 'x as int'
+    As {
+        expr: Box<Expression>,
+        ty: TypeExpression,
+    },
+    // Evaluates to the value of its last expression, or null if it ends with a statement
+    Block {
+        stmts: Vec<Statement>,
+        value: Option<Box<Expression>>,
+    },
+    If {
+        cond: Box<Expression>,
+        body: Box<Expression>,
+        else_branch: Option<Box<Expression>>,
+    },
+}
+
+// An argument in a call, which is matched to a parameter by position unless it is named
+#[derive(Debug, Clone, PartialEq)]
+pub struct Arg {
+    pub name: Option<Spanned<String>>,
+    pub value: Expression,
 }
 
 #[derive(Debug, Clone, PartialEq)]
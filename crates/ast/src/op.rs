@@ -18,13 +18,50 @@ pub enum Op {
     Not,
 }
 
+/// Name of the class method that overloads indexing (`instance[index]`)
+pub const INDEX_OVERLOAD_METHOD: &str = "op_index";
+
+/// Name of the class method used when an instance is converted to a string
+pub const TO_STRING_METHOD: &str = "to_string";
+
+/// Name of the class method used by `==` and `!=` when there is no `op_eq`
+pub const EQUALS_METHOD: &str = "equals";
+
+/// Name of the class method used by the `hash` builtin
+pub const HASH_METHOD: &str = "hash";
+
+/// Name of the class method that returns an iterator, making instances loopable
+pub const ITER_METHOD: &str = "iter";
+
+/// Name of the iterator method that returns the next value, or null once it is done
+pub const NEXT_METHOD: &str = "next";
+
+impl Op {
+    /// Name of the class method that overloads this operator, if it can be overloaded.
+    /// `!=` is dispatched to `op_eq` and negated.
+    pub fn overload_method(&self) -> Option<&'static str> {
+        match self {
+            Op::Add => Some("op_add"),
+            Op::Sub => Some("op_sub"),
+            Op::Mul => Some("op_mul"),
+            Op::Div => Some("op_div"),
+            Op::Equals | Op::NotEquals => Some("op_eq"),
+            Op::LessThan => Some("op_lt"),
+            Op::LessThanEquals => Some("op_lte"),
+            Op::GreaterThan => Some("op_gt"),
+            Op::GreaterThanEquals => Some("op_gte"),
+            Op::And | Op::Or | Op::Not => None,
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Operator {
     pub op: Op,
     pub span: Span,
 }
 
-impl<'a> From<&Token> for Operator {
+impl From<&Token> for Operator {
     fn from(token: &Token) -> Self {
         let op = match token.kind {
             TokenKind::Bang => Op::Not,
@@ -23,12 +23,35 @@ impl From<&Token> for Binding {
     }
 }
 
+// Function parameters can have a default value, which is evaluated in the callee's scope when the
+// caller leaves the argument out. A rest param ('...xs') collects the remaining arguments into a list
+#[derive(Debug, Clone, PartialEq)]
+pub struct Param {
+    pub name: Spanned<String>,
+    pub ty: Type,
+    pub default: Option<Expression>,
+    pub rest: bool,
+}
+
+impl From<Binding> for Param {
+    fn from(binding: Binding) -> Self {
+        Self {
+            name: binding.name,
+            ty: binding.ty,
+            default: None,
+            rest: false,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Function {
     pub name: Spanned<String>,
-    pub params: Vec<Binding>,
+    pub params: Vec<Param>,
     pub body: Box<Statement>,
     pub ty: TypeExpression,
+    // Functions that yield are generators, and calling one returns a suspended Type::Generator
+    pub generator: bool,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -36,6 +59,32 @@ pub struct Class {
     pub name: Spanned<String>,
     pub methods: Vec<Statement>,
     pub statics: Vec<Statement>,
+    pub fields: Vec<Statement>,
+    // Methods that are called when a property is read or assigned, named after the property
+    pub getters: Vec<Statement>,
+    pub setters: Vec<Statement>,
+}
+
+// Structs are copied instead of shared, and their fields are the params of their constructor
+#[derive(Debug, Clone, PartialEq)]
+pub struct Struct {
+    pub name: Spanned<String>,
+    pub fields: Vec<Param>,
+}
+
+// Destructuring let bindings, either 'let [a, b] = list;' or 'let {x, y} = instance;'
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pattern {
+    List(Vec<Binding>),
+    Fields(Vec<Binding>),
+}
+
+impl Pattern {
+    pub fn bindings(&self) -> &[Binding] {
+        match self {
+            Self::List(bindings) | Self::Fields(bindings) => bindings,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -43,11 +92,16 @@ pub enum Stmt {
     Expr(Expression),
     Function(Function),
     Class(Class),
+    Struct(Struct),
     Return(Option<Expression>),
     Variable {
         name: Binding,
         expr: Option<Expression>,
     },
+    Destructure {
+        pattern: Pattern,
+        expr: Expression,
+    },
     Block(Vec<Statement>),
     If {
         cond: Expression,
@@ -58,6 +112,17 @@ pub enum Stmt {
         cond: Expression,
         body: Box<Statement>,
     },
+    For {
+        binding: Binding,
+        iterable: Expression,
+        body: Box<Statement>,
+    },
+    Yield(Expression),
+    // Aliases are resolved by the parser, so they do nothing at runtime
+    TypeAlias {
+        name: Spanned<String>,
+        ty: TypeExpression,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq)]
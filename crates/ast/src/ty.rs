@@ -3,7 +3,7 @@ use meta::Span;
 use crate::{
     expr::Expression,
     op::{Op, Operator},
-    stmt::Binding,
+    stmt::{Binding, Param},
 };
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -15,10 +15,20 @@ pub enum Type {
     List(Box<TypeExpression>),
     Class(String),
     Instance(String),
+    // Variadic functions collect any extra arguments into their last param, which is a list
     Function {
         params: Vec<TypeExpression>,
         returns: Box<TypeExpression>,
+        variadic: bool,
     },
+    // Produces values of the inner type lazily, see Stmt::Yield
+    Generator(Box<TypeExpression>),
+    // Fixed size and heterogenous, unlike List. Ex: (int, string)
+    Tuple(Vec<TypeExpression>),
+    // A value of any one of the types. Ex: int | string
+    Union(Vec<TypeExpression>),
+    // The members of an enum. They are ints at runtime, but members of different enums don't mix
+    Enum(String),
     Null,
     Any,
 }
@@ -54,6 +64,15 @@ impl From<Binding> for TypeExpression {
     }
 }
 
+impl From<Param> for TypeExpression {
+    fn from(param: Param) -> Self {
+        Self {
+            span: param.name.span,
+            ty: param.ty,
+        }
+    }
+}
+
 impl std::fmt::Display for Type {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let msg: String = match self {
@@ -64,15 +83,36 @@ impl std::fmt::Display for Type {
             Self::List(ty) => format!("[{}]", ty.ty),
             Self::Class(name) => name.clone(),
             Self::Instance(name) => format!("instanceof {name}"),
-            Self::Function { params, returns } => format!(
-                "({}) -> {}",
-                params
+            Self::Enum(name) => name.clone(),
+            Self::Function {
+                params,
+                returns,
+                variadic,
+            } => {
+                let mut params = params
                     .iter()
                     .map(|p| format!("{}", p.ty))
-                    .collect::<Vec<_>>()
-                    .join(", "),
-                returns.ty
-            ),
+                    .collect::<Vec<_>>();
+                if let (true, Some(rest)) = (variadic, params.last_mut()) {
+                    rest.insert_str(0, "...");
+                }
+
+                format!("({}) -> {}", params.join(", "), returns.ty)
+            }
+            Self::Generator(ty) => format!("generator<{}>", ty.ty),
+            Self::Tuple(tys) => {
+                let tys = tys.iter().map(|t| format!("{}", t.ty)).collect::<Vec<_>>();
+                if tys.len() == 1 {
+                    format!("({},)", tys[0])
+                } else {
+                    format!("({})", tys.join(", "))
+                }
+            }
+            Self::Union(tys) => tys
+                .iter()
+                .map(|t| format!("{}", t.ty))
+                .collect::<Vec<_>>()
+                .join(" | "),
             Self::Null => String::from("null"),
             Self::Any => String::from("any"),
         };
@@ -87,17 +127,27 @@ pub fn type_compatible(lhs: &Type, rhs: &Type) -> bool {
         (Type::Float, Type::Int) => true,
         (Type::Null, _) => true,
         (Type::List(lhs), Type::List(rhs)) => type_compatible(&lhs.ty, &rhs.ty),
+        (Type::Generator(lhs), Type::Generator(rhs)) => type_compatible(&lhs.ty, &rhs.ty),
+        (Type::Tuple(lhs), Type::Tuple(rhs)) => {
+            lhs.len() == rhs.len()
+                && lhs
+                    .iter()
+                    .zip(rhs.iter())
+                    .all(|(lhs, rhs)| type_compatible(&lhs.ty, &rhs.ty))
+        }
         (
             Type::Function {
                 params: lhs_params,
                 returns: lhs_returns,
+                variadic: lhs_variadic,
             },
             Type::Function {
                 params: rhs_params,
                 returns: rhs_returns,
+                variadic: rhs_variadic,
             },
         ) => {
-            if lhs_params.len() != rhs_params.len() {
+            if lhs_params.len() != rhs_params.len() || lhs_variadic != rhs_variadic {
                 return false;
             }
 
@@ -116,6 +166,11 @@ pub fn type_compatible(lhs: &Type, rhs: &Type) -> bool {
             true
         }
 
+        // A union accepts anything that one of its types accepts, but a union value is only
+        // accepted if all of its types are
+        (_, Type::Union(rhs)) => rhs.iter().all(|rhs| type_compatible(lhs, &rhs.ty)),
+        (Type::Union(lhs), rhs) => lhs.iter().any(|lhs| type_compatible(&lhs.ty, rhs)),
+
         (Type::Any, _) => true,
         (_, Type::Any) => true,
 
@@ -204,21 +259,22 @@ pub fn result_type(lhs: &Expression, op: &Operator, rhs: &Expression) -> Option<
         },
         Op::LessThan | Op::LessThanEquals | Op::GreaterThan | Op::GreaterThanEquals => {
             match (&lhs.ty, &rhs.ty) {
-                (Type::Int, Type::Int) => Some(Type::Int),
-                (Type::Int, Type::Float) => Some(Type::Float),
-                (Type::Float, Type::Int) => Some(Type::Float),
-                (Type::Float, Type::Float) => Some(Type::Float),
+                (Type::Int | Type::Float, Type::Int | Type::Float) => Some(Type::Bool),
+                (Type::Enum(lhs), Type::Enum(rhs)) if lhs == rhs => Some(Type::Bool),
 
                 (Type::Null, _) => None,
                 (_, Type::Null) => None,
 
-                (Type::Any, _) => Some(Type::Any),
-                (_, Type::Any) => Some(Type::Any),
+                (Type::Any, _) => Some(Type::Bool),
+                (_, Type::Any) => Some(Type::Bool),
 
                 _ => None,
             }
         }
-        Op::Equals | Op::NotEquals => Some(Type::Bool),
+        Op::Equals | Op::NotEquals => match (&lhs.ty, &rhs.ty) {
+            (Type::Enum(lhs), Type::Enum(rhs)) if lhs != rhs => None,
+            _ => Some(Type::Bool),
+        },
         _ => None,
     }
 }
@@ -63,7 +63,7 @@ impl Codegen for Expression {
                     // TODO - deal with builtins better
                     Expr::Variable(name) => {
                         if name == "print" {
-                            Ok(format!("std::cout << {}", args.get(0).unwrap().codegen()?))
+                            Ok(format!("std::cout << {}", args.first().unwrap().value.codegen()?))
                         } else {
                             todo!("codegen for non print")
                         }
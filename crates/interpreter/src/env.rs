@@ -1,9 +1,9 @@
-use ast::ty::Type;
+use ast::{stmt::Statement, ty::Type};
 use meta::{trace, Span, Spanned};
 
 use crate::{
     error::RuntimeError,
-    value::{self, Builtin, Instance, InstanceId, List, Val, Value},
+    value::{self, Builtin, Generator, GeneratorId, Instance, InstanceId, List, Val, Value},
 };
 use std::collections::{hash_map::Entry, HashMap};
 
@@ -18,6 +18,8 @@ pub struct Environment {
     next_instance_id: InstanceId,
     lists: Vec<List>,
     next_list_id: ListId,
+    generators: Vec<Generator>,
+    next_generator_id: GeneratorId,
 }
 
 impl Environment {
@@ -29,6 +31,8 @@ impl Environment {
             next_instance_id: 0,
             lists: vec![],
             next_list_id: 0,
+            generators: vec![],
+            next_generator_id: 0,
         };
 
         env.define_builtins(builtins)?;
@@ -93,6 +97,81 @@ impl Environment {
             .expect("ICE: Called get_list on list that doesn't exist")
     }
 
+    // Structs are values, so the lists inside them are copied along with them. Anything else,
+    // including a struct inside a class instance, is left shared.
+    pub fn copy_struct(&mut self, value: Value) -> Value {
+        match value.val {
+            Val::StructInstance { .. } | Val::Tuple(..) => {
+                self.copy_value(value, &mut HashMap::default())
+            }
+            _ => value,
+        }
+    }
+
+    // Keeps track of the lists it has already copied so a list that contains itself terminates
+    fn copy_value(&mut self, value: Value, copies: &mut HashMap<ListId, ListId>) -> Value {
+        let val = match value.val {
+            Val::StructInstance { name, fields } => Val::StructInstance {
+                name,
+                fields: fields
+                    .into_iter()
+                    .map(|(field, val)| (field, self.copy_value(val, copies)))
+                    .collect(),
+            },
+            Val::Tuple(vals) => Val::Tuple(
+                vals.into_iter()
+                    .map(|val| self.copy_value(val, copies))
+                    .collect(),
+            ),
+            Val::List { id } => match copies.get(&id) {
+                Some(copy) => Val::List { id: *copy },
+                None => {
+                    let copy = self.next_list_id;
+                    self.next_list_id += 1;
+                    self.lists.push(List::new(copy, vec![]));
+                    copies.insert(id, copy);
+
+                    let vals = self.get_list(id).values().to_vec();
+                    let vals = vals
+                        .into_iter()
+                        .map(|val| self.copy_value(val, copies))
+                        .collect();
+                    *self.get_list(copy) = List::new(copy, vals);
+
+                    Val::List { id: copy }
+                }
+            },
+            val => val,
+        };
+
+        Value { val, ..value }
+    }
+
+    pub fn new_generator(
+        &mut self,
+        body: Statement,
+        scope: ScopeId,
+        ty: Type,
+        span: Span,
+    ) -> Value {
+        let id = self.next_generator_id;
+        self.next_generator_id += 1;
+
+        self.generators.push(Generator::new(id, body, scope, span));
+
+        Value {
+            ty,
+            span,
+            val: Val::Generator { id },
+        }
+    }
+
+    pub fn get_generator(&mut self, generator_id: GeneratorId) -> &mut Generator {
+        self.generators
+            .get_mut(generator_id)
+            .expect("ICE: Called get_generator on generator that doesn't exist")
+    }
+
     pub fn begin_scope(&mut self, enclosing: ScopeId) -> ScopeId {
         let id = self.next_scope_id;
         self.next_scope_id += 1;
@@ -166,6 +245,7 @@ impl Environment {
         name: String,
         val: Value,
     ) -> Result<(), RuntimeError> {
+        let val = self.copy_struct(val);
         let scope = self
             .scopes
             .get_mut(scope_id)
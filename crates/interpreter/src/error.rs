@@ -10,7 +10,7 @@ pub enum RuntimeError {
     // This should never bubble up all the way to the top, and the parser should prevent using
     // return in non function contexts
     #[error("Early Return")]
-    EarlyReturn(Value),
+    EarlyReturn(Box<Value>),
 
     #[error("Arity mismatch")]
     #[diagnostic(code(nak_runtime::arity_mismatch))]
@@ -21,6 +21,66 @@ pub enum RuntimeError {
         usize,
     ),
 
+    #[error("Unknown parameter")]
+    #[diagnostic(code(nak_runtime::unknown_parameter))]
+    UnknownParameter(
+        SourceId,
+        #[label("This function has no parameter named '{2}'")] SourceSpan,
+        String,
+    ),
+
+    #[error("Missing argument")]
+    #[diagnostic(code(nak_runtime::missing_argument))]
+    MissingArgument(
+        SourceId,
+        #[label("No argument was given for '{2}'")] SourceSpan,
+        String,
+    ),
+
+    #[error("Destructure length mismatch")]
+    #[diagnostic(code(nak_runtime::destructure_length_mismatch))]
+    DestructureLengthMismatch(
+        SourceId,
+        #[label("Expected a list with {2} values, but it has {3}")] SourceSpan,
+        usize,
+        usize,
+    ),
+
+    #[error("Tuple index out of bounds")]
+    #[diagnostic(code(nak_runtime::tuple_index_out_of_bounds))]
+    TupleIndexOutOfBounds(
+        SourceId,
+        #[label("Tried to get element {2} of a tuple with {3} elements")] SourceSpan,
+        usize,
+        usize,
+    ),
+
+    #[error("Negative index")]
+    #[diagnostic(code(nak_runtime::negative_index), help("Lists are indexed from 0"))]
+    NegativeIndex(
+        SourceId,
+        #[label("This is {2}, which can't be used as an index")] SourceSpan,
+        i64,
+    ),
+
+    #[error("Not iterable")]
+    #[diagnostic(code(nak_runtime::not_iterable))]
+    NotIterable(
+        SourceId,
+        #[label("This has type {2}, which can't be looped over")] SourceSpan,
+        Type,
+    ),
+
+    #[error("Generator already running")]
+    #[diagnostic(
+        code(nak_runtime::generator_already_running),
+        help("A generator can't ask itself for its next value")
+    )]
+    GeneratorAlreadyRunning(
+        SourceId,
+        #[label("This generator is already running")] SourceSpan,
+    ),
+
     #[error("Undefined variable")]
     #[diagnostic(code(nak_runtime::unknown_variable))]
     UndefinedVariable(SourceId, #[label("Undefined variable")] SourceSpan),
@@ -34,15 +94,24 @@ pub enum RuntimeError {
         #[label("This value is not of type {1}")] SourceSpan,
     ),
 
+    #[error("Invalid cast")]
+    #[diagnostic(code(nak_runtime::invalid_cast))]
+    InvalidCast(
+        SourceId,
+        #[label("Expected a value of type {2}, but got {3}")] SourceSpan,
+        Type,
+        String,
+    ),
+
     #[error("Unsupported operation")]
     #[diagnostic(code(nak_runtime::unexpected_operation))]
     UnsupportedOperation(
         SourceId,
         #[label("This operation doesn't support these types")] SourceSpan,
         #[label("{3}")] SourceSpan,
-        Type,
+        Box<Type>,
         #[label("{5}")] SourceSpan,
-        Type,
+        Box<Type>,
     ),
 
     #[error("Undefined class property")]
@@ -1,9 +1,14 @@
 use crate::{
-    env::{Environment, ScopeId},
+    env::{Environment, ListId, ScopeId},
     error::RuntimeError,
-    value::{Callable, Indexible, Val, Value},
+    eval_stmt, resume_generator,
+    value::{Callable, GeneratorId, Indexible, Iter, Val, Value},
+};
+use ast::{
+    expr::*,
+    op::{Op, EQUALS_METHOD, INDEX_OVERLOAD_METHOD},
+    ty::Type,
 };
-use ast::{expr::*, op::Op};
 use meta::{Span, Spanned};
 
 pub(crate) fn eval_expr(
@@ -15,6 +20,7 @@ pub(crate) fn eval_expr(
         Expr::Bool(..) | Expr::Int(..) | Expr::Float(..) | Expr::String(..) | Expr::Null => {
             Ok(expr.into())
         }
+        Expr::Grouping(inner) => eval_expr(*inner, env, scope),
         Expr::Variable(..) => eval_variable_expr(expr, env, scope),
         Expr::Assign { .. } => eval_assign_expr(expr, env, scope),
         Expr::Call { .. } => eval_call_expr(expr, env, scope),
@@ -23,10 +29,20 @@ pub(crate) fn eval_expr(
         Expr::Get { .. } => eval_get_expr(expr, env, scope),
         Expr::Set { .. } => eval_set_expr(expr, env, scope),
         Expr::This => eval_this_expr(expr, env, scope),
+        Expr::Is { .. } => eval_is_expr(expr, env, scope),
+        Expr::As { .. } => eval_as_expr(expr, env, scope),
         Expr::List(..) => eval_list_expr(expr, env, scope),
+        Expr::Block { .. } => eval_block_expr(expr, env, scope),
+        Expr::If { .. } => eval_if_expr(expr, env, scope),
+        Expr::Tuple(..) => eval_tuple_expr(expr, env, scope),
+        Expr::TupleGet { .. } => eval_tuple_get_expr(expr, env, scope),
         Expr::IndexGet { .. } => eval_index_get_expr(expr, env, scope),
         Expr::IndexSet { .. } => eval_index_set_expr(expr, env, scope),
         Expr::ListShorthand { .. } => eval_list_shorthand_expr(expr, env, scope),
+        Expr::ListComprehension { .. } => eval_list_comprehension_expr(expr, env, scope),
+        Expr::Spread(..) => {
+            unreachable!("ICE: spread expressions should only be evaluated by calls and lists")
+        }
         _ => todo!("{:#?} nyi", expr),
     }
 }
@@ -56,6 +72,7 @@ fn eval_assign_expr(
 ) -> Result<Value, RuntimeError> {
     if let Expr::Assign { name, rhs } = expr.expr {
         let val = eval_expr(*rhs, env, scope)?;
+        let val = env.copy_struct(val);
 
         env.assign(scope, name, val)?;
 
@@ -72,11 +89,24 @@ fn eval_call_expr(
 ) -> Result<Value, RuntimeError> {
     if let Expr::Call { callee, args, .. } = expr.expr {
         let callee_span = callee.span;
-        let val = eval_expr(*callee, env, scope)?;
+        let val = match callee.expr {
+            Expr::Get { object, name } => {
+                let obj = eval_expr(*object, env, scope)?;
+
+                // Generator methods are built in, so they aren't values of their own
+                if let Val::Generator { id } = obj.val {
+                    return eval_generator_method(id, name, env);
+                }
+
+                eval_property(obj, name, env)?
+            }
+            _ => eval_expr(*callee, env, scope)?,
+        };
 
         match val.val {
             Val::Function(func) => func.call(callee_span, args, env, scope),
             Val::Class(class) => class.call(callee_span, args, env, scope),
+            Val::Struct(strukt) => strukt.call(callee_span, args, env, scope),
             Val::Builtin(builtin) => builtin.call(callee_span, args, env, scope),
             _ => panic!("ICE: can only call t"),
         }
@@ -94,6 +124,24 @@ fn eval_binary_expr(
         let lhs = eval_expr(*lhs, env, scope)?;
         let rhs = eval_expr(*rhs, env, scope)?;
 
+        // Instances can overload operators with methods like 'op_add'
+        if let (Val::Instance { .. }, Some(method)) = (&lhs.val, op.op.overload_method()) {
+            if let Some(val) = lhs.call_method(env, method, vec![rhs.clone()], expr.span)? {
+                return match op.op {
+                    Op::NotEquals => Ok((!val.as_bool()?, expr.span).into()),
+                    _ => Ok(val),
+                };
+            }
+        }
+
+        // Without 'op_eq', equality falls back to an 'equals' method
+        if let (Val::Instance { .. }, Op::Equals | Op::NotEquals) = (&lhs.val, op.op) {
+            if let Some(val) = lhs.call_method(env, EQUALS_METHOD, vec![rhs.clone()], expr.span)? {
+                let equal = val.as_bool()?;
+                return Ok((equal == (op.op == Op::Equals), expr.span).into());
+            }
+        }
+
         match op.op {
             Op::Add => lhs.add(env, op, &rhs),
             Op::Sub => lhs.sub(op, &rhs),
@@ -101,6 +149,7 @@ fn eval_binary_expr(
             Op::Div => lhs.div(op, &rhs),
             Op::Equals => lhs.eq(&rhs),
             Op::NotEquals => lhs.neq(&rhs),
+            Op::LessThan => lhs.lt(op, &rhs),
             Op::LessThanEquals => lhs.lte(op, &rhs),
             Op::GreaterThan => lhs.gt(op, &rhs),
             Op::GreaterThanEquals => lhs.gte(op, &rhs),
@@ -121,30 +170,64 @@ fn eval_get_expr(
 ) -> Result<Value, RuntimeError> {
     if let Expr::Get { object, name } = expr.expr {
         let obj = eval_expr(*object, env, scope)?;
-        if let Val::Instance { .. } = obj.val {
-            let instance = env.get_instance(obj.as_instance()?)?;
+        eval_property(obj, name, env)
+    } else {
+        panic!("ICE: eval_get_expr should only be called with Expr::Get");
+    }
+}
 
-            // If property is a function, bind 'this'
-            let mut prop = instance.get_property(&name.item)?;
-            if let Val::Function(..) = prop.val {
-                prop.bind_this(env, obj)?;
-            }
+pub(crate) fn eval_property(
+    obj: Value,
+    name: Spanned<String>,
+    env: &mut Environment,
+) -> Result<Value, RuntimeError> {
+    if let Val::StructInstance { .. } = obj.val {
+        obj.get_field(&name)
+    } else if let Val::Instance { .. } = obj.val {
+        let instance = env.get_instance(obj.as_instance()?)?;
 
-            Ok(prop)
-        } else {
-            let class = obj.as_class()?;
-            if let Some(entry) = class.statics.get(&name.item) {
-                Ok(entry.clone())
-            } else {
-                Err(RuntimeError::UndefinedClassProperty(
-                    class.class.name.span.source_id,
-                    class.class.name.span.into(),
-                    name.item.to_string(),
-                ))
-            }
+        // Getters are called instead of being returned, so they look like fields
+        if let Some(mut getter) = instance.getter(&name.item) {
+            getter.bind_this(env, obj)?;
+            return getter
+                .as_function()?
+                .call_with_values(name.span, vec![], env);
+        }
+
+        // If property is a function, bind 'this'
+        let mut prop = instance.get_property(&name.item)?;
+        if let Val::Function(..) = prop.val {
+            prop.bind_this(env, obj)?;
         }
+
+        Ok(prop)
     } else {
-        panic!("ICE: eval_get_expr should only be called with Expr::Get");
+        let class = obj.as_class()?;
+        if let Some(entry) = class.statics.get(&name.item) {
+            Ok(entry.clone())
+        } else {
+            Err(RuntimeError::UndefinedClassProperty(
+                class.class.name.span.source_id,
+                class.class.name.span.into(),
+                name.item.to_string(),
+            ))
+        }
+    }
+}
+
+// 'next' returns null once the generator has finished
+fn eval_generator_method(
+    id: GeneratorId,
+    name: Spanned<String>,
+    env: &mut Environment,
+) -> Result<Value, RuntimeError> {
+    match name.item.as_str() {
+        "next" => Ok(resume_generator(id, env)?.unwrap_or_else(Value::null)),
+        _ => Err(RuntimeError::UndefinedClassProperty(
+            name.span.source_id,
+            name.span.into(),
+            name.item,
+        )),
     }
 }
 
@@ -154,16 +237,136 @@ fn eval_set_expr(
     scope: ScopeId,
 ) -> Result<Value, RuntimeError> {
     if let Expr::Set { object, name, rhs } = expr.expr {
-        let obj = eval_expr(*object, env, scope)?;
+        let (obj, place) = eval_place(*object, env, scope)?;
         let val = eval_expr(*rhs, env, scope)?;
+        let val = env.copy_struct(val);
 
-        let instance = env.get_instance(obj.as_instance()?)?;
-        instance.set_property(name.item, val)
+        set_property(place, obj, name, val, env)
     } else {
         panic!("ICE: eval_set_expr should only be called with Expr::Set");
     }
 }
 
+// Where the object of an assignment came from. Structs are values, so a changed copy has to be
+// written back there.
+enum Place {
+    Variable {
+        name: Spanned<String>,
+        scope: ScopeId,
+    },
+    Property {
+        place: Box<Place>,
+        object: Value,
+        name: Spanned<String>,
+    },
+    Index {
+        list: ListId,
+        index: usize,
+    },
+    Temporary,
+}
+
+// Evaluates an expression along with the place it came from, evaluating every part only once
+fn eval_place(
+    expr: Expression,
+    env: &mut Environment,
+    scope: ScopeId,
+) -> Result<(Value, Place), RuntimeError> {
+    match expr.expr {
+        Expr::Grouping(inner) => eval_place(*inner, env, scope),
+        Expr::Variable(name) => {
+            let name = Spanned {
+                item: name,
+                span: expr.span,
+            };
+            Ok((env.get(scope, &name)?, Place::Variable { name, scope }))
+        }
+        Expr::Get { object, name } => {
+            let (object, place) = eval_place(*object, env, scope)?;
+            let val = eval_property(object.clone(), name.clone(), env)?;
+            let place = Place::Property {
+                place: Box::new(place),
+                object,
+                name,
+            };
+            Ok((val, place))
+        }
+        Expr::IndexGet { lhs, index } => {
+            let lhs = eval_expr(*lhs, env, scope)?;
+            let index = eval_expr(*index, env, scope)?;
+            let place = match lhs.val {
+                Val::List { id } => Place::Index {
+                    list: id,
+                    index: list_index(&index)?,
+                },
+                _ => Place::Temporary,
+            };
+            Ok((eval_index(lhs, index, expr.span, env)?, place))
+        }
+        _ => Ok((eval_expr(expr, env, scope)?, Place::Temporary)),
+    }
+}
+
+fn set_property(
+    place: Place,
+    obj: Value,
+    name: Spanned<String>,
+    val: Value,
+    env: &mut Environment,
+) -> Result<Value, RuntimeError> {
+    if let Val::StructInstance {
+        name: struct_name,
+        mut fields,
+    } = obj.val
+    {
+        match fields.iter_mut().find(|(field, _)| *field == name.item) {
+            Some((_, field)) => *field = val,
+            None => {
+                return Err(RuntimeError::UndefinedClassProperty(
+                    name.span.source_id,
+                    name.span.into(),
+                    name.item,
+                ))
+            }
+        }
+
+        let updated = Value {
+            val: Val::StructInstance {
+                name: struct_name,
+                fields,
+            },
+            ..obj
+        };
+        match place {
+            Place::Variable { name, scope } => env.assign(scope, name, updated)?,
+            Place::Property {
+                place,
+                object,
+                name,
+            } => {
+                set_property(*place, object, name, updated, env)?;
+            }
+            Place::Index { list, index } => env.get_list(list).set(index, updated)?,
+            // There is nowhere to write a temporary back to
+            Place::Temporary => {}
+        }
+
+        Ok(Value::null())
+    } else {
+        let instance = env.get_instance(obj.as_instance()?)?;
+        if let Some(mut setter) = instance.setter(&name.item) {
+            setter.bind_this(env, obj)?;
+            setter
+                .as_function()?
+                .call_with_values(name.span, vec![val], env)?;
+            return Ok(Value::null());
+        }
+
+        let instance = env.get_instance(obj.as_instance()?)?;
+        instance.set_property(name.item, val)
+    }
+}
+
 fn eval_logical_expr(
     expr: Expression,
     env: &mut Environment,
@@ -220,6 +423,46 @@ fn eval_this_expr(
     }
 }
 
+fn eval_is_expr(
+    expr: Expression,
+    env: &mut Environment,
+    scope: ScopeId,
+) -> Result<Value, RuntimeError> {
+    if let Expr::Is { expr: inner, ty } = expr.expr {
+        let val = eval_expr(*inner, env, scope)?;
+        Ok((val.is(&ty.ty, env), expr.span).into())
+    } else {
+        panic!("ICE: eval_is_expr should only be called with Expr::Is");
+    }
+}
+
+fn eval_as_expr(
+    expr: Expression,
+    env: &mut Environment,
+    scope: ScopeId,
+) -> Result<Value, RuntimeError> {
+    if let Expr::As { expr: inner, ty } = expr.expr {
+        let mut val = eval_expr(*inner, env, scope)?;
+        if !val.is(&ty.ty, env) {
+            return Err(RuntimeError::InvalidCast(
+                expr.span.source_id,
+                expr.span.into(),
+                ty.ty,
+                val.to_string(env)?,
+            ));
+        }
+
+        // An enum member cast to int is just its value
+        if let (Type::Int, Type::Enum(..)) = (&ty.ty, &val.ty) {
+            val.ty = Type::Int;
+        }
+
+        Ok(val)
+    } else {
+        panic!("ICE: eval_as_expr should only be called with Expr::As");
+    }
+}
+
 fn eval_list_expr(
     expr: Expression,
     env: &mut Environment,
@@ -228,7 +471,10 @@ fn eval_list_expr(
     if let Expr::List(list) = expr.expr {
         let mut vals = vec![];
         for val in list.into_iter() {
-            vals.push(eval_expr(val, env, scope)?);
+            match val.expr {
+                Expr::Spread(inner) => vals.extend(eval_spread(*inner, env, scope)?),
+                _ => vals.push(eval_expr(val, env, scope)?),
+            }
         }
 
         Ok(env.new_list(vals, expr.ty.clone()))
@@ -237,6 +483,96 @@ fn eval_list_expr(
     }
 }
 
+fn eval_block_expr(
+    expr: Expression,
+    env: &mut Environment,
+    scope: ScopeId,
+) -> Result<Value, RuntimeError> {
+    if let Expr::Block { stmts, value } = expr.expr {
+        let new_scope = env.begin_scope(scope);
+
+        for stmt in stmts {
+            eval_stmt(stmt, env, new_scope)?;
+        }
+
+        let value = value.map_or(Ok(Value::null()), |value| eval_expr(*value, env, new_scope))?;
+
+        env.delete_scope(new_scope);
+
+        Ok(value)
+    } else {
+        panic!("ICE: eval_block_expr should only be called with Expr::Block");
+    }
+}
+
+fn eval_if_expr(
+    expr: Expression,
+    env: &mut Environment,
+    scope: ScopeId,
+) -> Result<Value, RuntimeError> {
+    if let Expr::If {
+        cond,
+        body,
+        else_branch,
+    } = expr.expr
+    {
+        if eval_expr(*cond, env, scope)?.as_bool()? {
+            eval_expr(*body, env, scope)
+        } else if let Some(else_branch) = else_branch {
+            eval_expr(*else_branch, env, scope)
+        } else {
+            Ok(Value::null())
+        }
+    } else {
+        panic!("ICE: eval_if_expr should only be called with Expr::If");
+    }
+}
+
+fn eval_tuple_expr(
+    expr: Expression,
+    env: &mut Environment,
+    scope: ScopeId,
+) -> Result<Value, RuntimeError> {
+    if let Expr::Tuple(elems) = expr.expr {
+        let vals = elems
+            .into_iter()
+            .map(|elem| eval_expr(elem, env, scope))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Value {
+            val: Val::Tuple(vals),
+            span: expr.span,
+            ty: expr.ty,
+        })
+    } else {
+        panic!("ICE: eval_tuple_expr should only be called with Expr::Tuple");
+    }
+}
+
+fn eval_tuple_get_expr(
+    expr: Expression,
+    env: &mut Environment,
+    scope: ScopeId,
+) -> Result<Value, RuntimeError> {
+    if let Expr::TupleGet { tuple, index } = expr.expr {
+        let tuple = eval_expr(*tuple, env, scope)?;
+        let vals = tuple.as_tuple()?;
+
+        // The parser has already checked the index, unless the tuple is of type any
+        match vals.get(index.item) {
+            Some(val) => Ok(val.clone()),
+            None => Err(RuntimeError::TupleIndexOutOfBounds(
+                index.span.source_id,
+                index.span.into(),
+                index.item,
+                vals.len(),
+            )),
+        }
+    } else {
+        panic!("ICE: eval_tuple_get_expr should only be called with Expr::TupleGet");
+    }
+}
+
 fn eval_index_get_expr(
     expr: Expression,
     env: &mut Environment,
@@ -245,16 +581,40 @@ fn eval_index_get_expr(
     if let Expr::IndexGet { lhs, index } = expr.expr {
         let lhs = eval_expr(*lhs, env, scope)?;
         let index = eval_expr(*index, env, scope)?;
-
-        match lhs.val {
-            Val::List { id } => env.get_list(id).get(index.as_int()?.try_into().unwrap()),
-            _ => panic!("ICE: can only index Lists"),
-        }
+        eval_index(lhs, index, expr.span, env)
     } else {
         panic!("ICE: eval_index_get_expr should only be called with Expr::Index");
     }
 }
 
+fn eval_index(
+    lhs: Value,
+    index: Value,
+    span: Span,
+    env: &mut Environment,
+) -> Result<Value, RuntimeError> {
+    match lhs.val {
+        Val::List { id } => env.get_list(id).get(list_index(&index)?),
+        Val::Instance { .. } => {
+            match lhs.call_method(env, INDEX_OVERLOAD_METHOD, vec![index], span)? {
+                Some(val) => Ok(val),
+                None => Err(RuntimeError::UndefinedClassProperty(
+                    span.source_id,
+                    span.into(),
+                    INDEX_OVERLOAD_METHOD.to_string(),
+                )),
+            }
+        }
+        _ => panic!("ICE: can only index Lists"),
+    }
+}
+
+fn list_index(index: &Value) -> Result<usize, RuntimeError> {
+    let int = index.as_int()?;
+    int.try_into()
+        .map_err(|_| RuntimeError::NegativeIndex(index.span.source_id, index.span.into(), int))
+}
+
 fn eval_index_set_expr(
     expr: Expression,
     env: &mut Environment,
@@ -270,7 +630,7 @@ fn eval_index_set_expr(
             _ => panic!("ICE: can only index Lists"),
         };
 
-        lhs.set(index.as_int()?.try_into().unwrap(), rhs)?;
+        lhs.set(list_index(&index)?, rhs)?;
 
         Ok(Value::null())
     } else {
@@ -297,3 +657,73 @@ fn eval_list_shorthand_expr(
         panic!("ICE: eval_list_shorthand_expr should only be called with Expr::ListShorthand");
     }
 }
+
+fn eval_list_comprehension_expr(
+    expr: Expression,
+    env: &mut Environment,
+    scope: ScopeId,
+) -> Result<Value, RuntimeError> {
+    if let Expr::ListComprehension {
+        value,
+        binding,
+        iterable,
+        cond,
+    } = expr.expr
+    {
+        let mut iter = Iter::new(&eval_expr(*iterable, env, scope)?, env)?;
+
+        let mut vals = vec![];
+        while let Some(val) = iter.next(env)? {
+            let new_scope = env.begin_scope(scope);
+            env.define(new_scope, binding.name.item.clone(), val)?;
+
+            let keep = match &cond {
+                Some(cond) => eval_expr(*cond.clone(), env, new_scope)?.as_bool()?,
+                None => true,
+            };
+            if keep {
+                vals.push(eval_expr(*value.clone(), env, new_scope)?);
+            }
+
+            env.delete_scope(new_scope);
+        }
+
+        Ok(env.new_list(vals, expr.ty))
+    } else {
+        panic!(
+            "ICE: eval_list_comprehension_expr should only be called with Expr::ListComprehension"
+        );
+    }
+}
+
+fn eval_spread(
+    expr: Expression,
+    env: &mut Environment,
+    scope: ScopeId,
+) -> Result<Vec<Value>, RuntimeError> {
+    let list = eval_expr(expr, env, scope)?.as_list()?;
+    Ok(env.get_list(list).values().to_vec())
+}
+
+pub(crate) type NamedValues = Vec<(Spanned<String>, Value)>;
+
+// Evaluates call arguments in the caller's scope, expanding spread lists into positional arguments
+pub(crate) fn eval_args(
+    args: Vec<Arg>,
+    env: &mut Environment,
+    scope: ScopeId,
+) -> Result<(Vec<Value>, NamedValues), RuntimeError> {
+    let mut positional = vec![];
+    let mut named = vec![];
+    for arg in args {
+        if let Some(name) = arg.name {
+            named.push((name, eval_expr(arg.value, env, scope)?));
+        } else if let Expr::Spread(inner) = arg.value.expr {
+            positional.extend(eval_spread(*inner, env, scope)?);
+        } else {
+            positional.push(eval_expr(arg.value, env, scope)?);
+        }
+    }
+
+    Ok((positional, named))
+}
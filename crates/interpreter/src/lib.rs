@@ -5,12 +5,14 @@ mod value;
 
 use crate::env::{Environment, ScopeId};
 use crate::error::RuntimeError;
-use crate::expr::eval_expr;
+use crate::expr::{eval_expr, eval_property};
+use crate::value::{Frame, GeneratorId, Struct};
 use ast::stmt::*;
+use ast::ty::Type;
 use meta::trace;
 use parser::Parse;
 
-pub use crate::value::{Builtin, Val, Value};
+pub use crate::value::{Builtin, Iter, Val, Value};
 
 pub fn interpret(parse: Parse, env: &mut Environment) -> miette::Result<()> {
     for _stmt in parse.stmts {
@@ -30,17 +32,24 @@ fn eval_stmt(stmt: Statement, env: &mut Environment, scope: ScopeId) -> Result<(
         Stmt::Variable { .. } => {
             eval_variable(stmt, env, scope)?;
         }
+        Stmt::Destructure { .. } => {
+            eval_destructure(stmt, env, scope)?;
+        }
         Stmt::Block(..) => {
             eval_block(stmt, env, scope)?;
         }
         Stmt::Return(expr) => {
             let expr = expr.map_or(Ok(Value::null()), |expr| eval_expr(expr, env, scope))?;
-            return Err(RuntimeError::EarlyReturn(expr));
+            return Err(RuntimeError::EarlyReturn(Box::new(expr)));
         }
         Stmt::Function(..) => eval_func_decl(stmt, env, scope)?,
         Stmt::Class(..) => eval_class_decl(stmt, env, scope)?,
+        Stmt::Struct(..) => eval_struct_decl(stmt, env, scope)?,
         Stmt::If { .. } => eval_if(stmt, env, scope)?,
         Stmt::Until { .. } => eval_until(stmt, env, scope)?,
+        Stmt::For { .. } => eval_for(stmt, env, scope)?,
+        Stmt::Yield(..) => unreachable!("ICE: yield should only be evaluated by a generator"),
+        Stmt::TypeAlias { .. } => {}
     }
 
     Ok(())
@@ -72,6 +81,49 @@ fn eval_variable(
     }
 }
 
+fn eval_destructure(
+    stmt: Statement,
+    env: &mut Environment,
+    scope: ScopeId,
+) -> Result<(), RuntimeError> {
+    if let Stmt::Destructure { pattern, expr } = stmt.stmt {
+        let span = expr.span;
+        let val = eval_expr(expr, env, scope)?;
+
+        let vals = match &pattern {
+            Pattern::List(bindings) => {
+                let vals = match &val.val {
+                    Val::Tuple(vals) => vals.clone(),
+                    _ => env.get_list(val.as_list()?).values().to_vec(),
+                };
+                if vals.len() != bindings.len() {
+                    return Err(RuntimeError::DestructureLengthMismatch(
+                        span.source_id,
+                        span.into(),
+                        bindings.len(),
+                        vals.len(),
+                    ));
+                }
+
+                vals
+            }
+            // Properties are read like in a get expression, so getters are called
+            Pattern::Fields(bindings) => bindings
+                .iter()
+                .map(|binding| eval_property(val.clone(), binding.name.clone(), env))
+                .collect::<Result<Vec<_>, _>>()?,
+        };
+
+        for (binding, val) in pattern.bindings().iter().zip(vals) {
+            env.define(scope, binding.name.item.clone(), val)?;
+        }
+
+        Ok(())
+    } else {
+        panic!("ICE: eval_destructure should only be called with Stmt::Destructure");
+    }
+}
+
 fn eval_block(stmt: Statement, env: &mut Environment, scope: ScopeId) -> Result<(), RuntimeError> {
     if let Stmt::Block(stmts) = stmt.stmt {
         for _stmt in stmts {
@@ -99,6 +151,28 @@ fn eval_func_decl(
     }
 }
 
+fn eval_struct_decl(
+    stmt: Statement,
+    env: &mut Environment,
+    scope: ScopeId,
+) -> Result<(), RuntimeError> {
+    if let Stmt::Struct(strukt) = stmt.stmt {
+        let name = strukt.name.item.clone();
+        let val = Value {
+            ty: Type::Class(name.clone()),
+            val: Val::Struct(Box::new(Struct {
+                strukt,
+                closure: scope,
+            })),
+            span: stmt.span,
+        };
+
+        env.define(scope, name, val)
+    } else {
+        panic!("ICE: eval_struct_decl should only be called with Stmt::Struct");
+    }
+}
+
 fn eval_class_decl(
     stmt: Statement,
     env: &mut Environment,
@@ -169,3 +243,546 @@ fn eval_until(stmt: Statement, env: &mut Environment, scope: ScopeId) -> Result<
         panic!("ICE: eval_until should only be called with Stmt::Until");
     }
 }
+
+fn eval_for(stmt: Statement, env: &mut Environment, scope: ScopeId) -> Result<(), RuntimeError> {
+    if let Stmt::For {
+        binding,
+        iterable,
+        body,
+    } = stmt.stmt
+    {
+        let mut iter = Iter::new(&eval_expr(iterable, env, scope)?, env)?;
+        while let Some(val) = iter.next(env)? {
+            let new_scope = env.begin_scope(scope);
+            env.define(new_scope, binding.name.item.clone(), val)?;
+            eval_stmt(*body.clone(), env, new_scope)?;
+
+            env.delete_scope(new_scope);
+        }
+
+        Ok(())
+    } else {
+        panic!("ICE: eval_for should only be called with Stmt::For");
+    }
+}
+
+// Runs a generator until its next 'yield', returning None once its body has finished
+pub(crate) fn resume_generator(
+    id: GeneratorId,
+    env: &mut Environment,
+) -> Result<Option<Value>, RuntimeError> {
+    let generator = env.get_generator(id);
+    if generator.running {
+        return Err(RuntimeError::GeneratorAlreadyRunning(
+            generator.span.source_id,
+            generator.span.into(),
+        ));
+    }
+
+    // The frames are taken out while running, since the body has full access to the environment
+    let mut frames = std::mem::take(&mut generator.frames);
+    generator.running = true;
+
+    let result = run_frames(&mut frames, env);
+
+    let generator = env.get_generator(id);
+    generator.running = false;
+    if let Ok(Some(..)) = result {
+        generator.frames = frames;
+    }
+
+    result
+}
+
+fn run_frames(
+    frames: &mut Vec<Frame>,
+    env: &mut Environment,
+) -> Result<Option<Value>, RuntimeError> {
+    while let Some(frame) = frames.last_mut() {
+        // Find the next statement to run, popping the frames that are finished
+        let (stmt, scope) = match frame {
+            Frame::Block { stmts, next, scope } => match stmts.get(*next) {
+                Some(stmt) => {
+                    *next += 1;
+                    (stmt.clone(), *scope)
+                }
+                None => {
+                    frames.pop();
+                    continue;
+                }
+            },
+            // A loop is back on top once the body of its previous iteration has finished
+            Frame::Until {
+                cond,
+                body,
+                scope,
+                iteration,
+            } => {
+                if let Some(iteration) = iteration.take() {
+                    env.delete_scope(iteration);
+                }
+
+                let new_scope = env.begin_scope(*scope);
+                if eval_expr(cond.clone(), env, new_scope)?.as_bool()? {
+                    env.delete_scope(new_scope);
+                    frames.pop();
+                    continue;
+                }
+
+                *iteration = Some(new_scope);
+                (body.clone(), new_scope)
+            }
+            Frame::For {
+                name,
+                iter,
+                body,
+                scope,
+                iteration,
+            } => {
+                if let Some(iteration) = iteration.take() {
+                    env.delete_scope(iteration);
+                }
+
+                match iter.next(env)? {
+                    Some(val) => {
+                        let new_scope = env.begin_scope(*scope);
+                        env.define(new_scope, name.clone(), val)?;
+                        *iteration = Some(new_scope);
+                        (body.clone(), new_scope)
+                    }
+                    None => {
+                        frames.pop();
+                        continue;
+                    }
+                }
+            }
+        };
+
+        // Statements that can contain a 'yield' get their own frame, everything else is run as is
+        match stmt.stmt {
+            Stmt::Yield(expr) => return Ok(Some(eval_expr(expr, env, scope)?)),
+            Stmt::Return(..) => return Ok(None),
+            Stmt::Block(stmts) => frames.push(Frame::Block {
+                stmts,
+                next: 0,
+                scope,
+            }),
+            Stmt::If {
+                cond,
+                body,
+                else_branch,
+            } => {
+                let cond = eval_expr(cond, env, scope)?;
+                let new_scope = env.begin_scope(scope);
+
+                let branch = if cond.as_bool()? {
+                    Some(*body)
+                } else {
+                    else_branch.map(|else_branch| *else_branch)
+                };
+
+                if let Some(branch) = branch {
+                    frames.push(Frame::Block {
+                        stmts: vec![branch],
+                        next: 0,
+                        scope: new_scope,
+                    });
+                }
+            }
+            Stmt::Until { cond, body } => frames.push(Frame::Until {
+                cond,
+                body: *body,
+                scope,
+                iteration: None,
+            }),
+            Stmt::For {
+                binding,
+                iterable,
+                body,
+            } => frames.push(Frame::For {
+                name: binding.name.item,
+                iter: Iter::new(&eval_expr(iterable, env, scope)?, env)?,
+                body: *body,
+                scope,
+                iteration: None,
+            }),
+            _ => eval_stmt(stmt, env, scope)?,
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use expect_test::{expect, Expect};
+    use parser::{parse, source::Source, SymbolTable};
+    use std::cell::RefCell;
+
+    thread_local! {
+        static OUTPUT: RefCell<String> = const { RefCell::new(String::new()) };
+    }
+
+    fn builtins() -> Vec<Builtin> {
+        fn print(vals: Vec<Value>, env: &mut Environment) -> Result<Value, RuntimeError> {
+            let line = vals
+                .iter()
+                .map(|val| val.to_string(env))
+                .collect::<Result<Vec<_>, _>>()?
+                .join(" ");
+            OUTPUT.with_borrow_mut(|output| {
+                output.push_str(&line);
+                output.push('\n');
+            });
+            Ok(Value::null())
+        }
+
+        fn str(vals: Vec<Value>, env: &mut Environment) -> Result<Value, RuntimeError> {
+            let val = &vals[0];
+            Ok(Value {
+                val: Val::String(val.to_string(env)?),
+                span: val.span,
+                ty: Type::String,
+            })
+        }
+
+        fn hash(vals: Vec<Value>, env: &mut Environment) -> Result<Value, RuntimeError> {
+            let val = &vals[0];
+            Ok(Value {
+                val: Val::Int(val.hash(env)?),
+                span: val.span,
+                ty: Type::Int,
+            })
+        }
+
+        fn to_list(vals: Vec<Value>, env: &mut Environment) -> Result<Value, RuntimeError> {
+            let mut iter = Iter::new(&vals[0], env)?;
+            let mut values = vec![];
+            while let Some(val) = iter.next(env)? {
+                values.push(val);
+            }
+            Ok(env.new_list(values, Type::Any))
+        }
+
+        vec![
+            Builtin::variadic(String::from("print"), vec![], Type::Any, None, print),
+            Builtin::new(
+                String::from("str"),
+                vec![Type::Any],
+                Some(Type::String),
+                str,
+            ),
+            Builtin::new(String::from("hash"), vec![Type::Any], Some(Type::Int), hash),
+            Builtin::new(
+                String::from("to_list"),
+                vec![Type::Any],
+                Some(Type::List(Box::new(ast::ty::TypeExpression::any()))),
+                to_list,
+            ),
+        ]
+    }
+
+    // Runs the program and compares everything it printed
+    fn check(input: &str, expected: Expect) {
+        let builtins = builtins();
+        let symbols = builtins.iter().map(|builtin| builtin.as_symbol()).collect();
+        let source = Source::new(0, String::from(input), "".into());
+        let parse = parse(source, SymbolTable::new(symbols)).unwrap();
+
+        OUTPUT.with_borrow_mut(String::clear);
+        interpret(parse, &mut Environment::new(builtins).unwrap()).unwrap();
+        expected.assert_eq(&OUTPUT.with_borrow(String::clone));
+    }
+
+    const MONEY: &str = "
+        class Money {
+          dollars: int;
+          cents: int;
+          constructor(dollars: int, cents: int) {
+            this.dollars = dollars;
+            this.cents = cents;
+          }
+          to_string() -> string { ret \"$\" + this.dollars + \".\" + this.cents; }
+          equals(other: Money) -> bool {
+            ret this.dollars == other.dollars and this.cents == other.cents;
+          }
+          hash() -> int { ret this.dollars * 100 + this.cents; }
+        }
+        let price = Money(4, 50);
+    ";
+
+    #[test]
+    fn interpret_protocol_methods() {
+        check(
+            &format!(
+                "{MONEY}
+                print(price);
+                print(str(price));
+                print(\"Total: \" + price);
+                print(price == Money(4, 50), price != Money(4, 50), price == Money(1, 0));
+                print(hash(price), hash(price) == hash(Money(4, 50)));"
+            ),
+            expect![[r#"
+                $4.50
+                $4.50
+                Total: $4.50
+                true false false
+                450 true
+            "#]],
+        );
+    }
+
+    #[test]
+    fn interpret_hash() {
+        check(
+            "struct Point { x: int; y: int; }
+            print(hash(1) == hash(\"1\"), hash(1) == hash(1.0), hash(null) == hash(\"null\"));
+            print(hash(\"a\") == hash(\"a\"), hash((1, \"a\")) == hash((1, \"a\")));
+            print(hash(Point(1, 2)) == hash(Point(1, 2)), hash(Point(1, 2)) == hash(Point(2, 1)));",
+            expect![[r#"
+                false false false
+                true true
+                true false
+            "#]],
+        );
+    }
+
+    #[test]
+    fn interpret_iterator_protocol() {
+        check(
+            "class Countdown {
+              current: int;
+              constructor(from: int) { this.current = from; }
+              next() -> int | null {
+                if this.current == 0 { ret null; }
+                this.current = this.current - 1;
+                ret this.current + 1;
+              }
+            }
+            class Range {
+              to: int;
+              constructor(to: int) { this.to = to; }
+              iter() -> Countdown { ret Countdown(this.to); }
+            }
+            class Evens {
+              iter() { yield 2; yield 4; }
+            }
+            for n in Range(3) { print(n); }
+            print([n * 10 for n in Range(3) if n != 2]);
+            print(to_list(Range(2)), to_list(Evens()));",
+            expect![[r#"
+                3
+                2
+                1
+                [30,10]
+                [2,1] [2,4]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn interpret_enums() {
+        check(
+            "enum Status {
+              Ok = 200,
+              Created,
+              NotFound = 404,
+              Teapot = -418,
+              Next,
+
+              is_error(status: Status) { ret status as int >= 400; }
+            }
+            print(Status.Ok, Status.Created, Status.NotFound, Status.Teapot, Status.Next);
+            print(Status.name(Status.Created), Status.values());
+            print(Status.is_error(Status.Ok), Status.is_error(Status.NotFound));
+            print(Status.Ok == Status.Ok, Status.Ok == Status.Created, Status.Ok is Status);",
+            expect![[r#"
+                200 201 404 -418 -417
+                Created [200,201,404,-418,-417]
+                false true
+                true false true
+            "#]],
+        );
+    }
+
+    #[test]
+    fn interpret_overloads() {
+        check(
+            "func show(x: any) -> string { ret \"any\"; }
+            func show(x: int) -> string { ret \"int\"; }
+            func show(x: int, y: string) -> string { ret \"int, string\"; }
+            class Scaler {
+              factor: int = 2;
+              scale(by: int) -> int { ret by * this.factor; }
+              scale(label: string) -> string { ret label + \" x\" + this.factor; }
+            }
+            let s = Scaler();
+            print(show(1), show(\"a\"), show(1, \"b\"), show(y: \"c\", x: 2));
+            print(s.scale(3), s.scale(\"size\"));",
+            expect![[r#"
+                int any int, string int, string
+                6 size x2
+            "#]],
+        );
+    }
+
+    #[test]
+    fn interpret_properties() {
+        check(
+            "class Rect {
+              priv w: int = 1;
+              get area() -> int { ret this.w * 2; }
+              set width(value: int) { this.w = value; }
+              get height() -> int { ret 2; }
+              set height(value: int) { print(\"height\", value); }
+            }
+            let r = Rect();
+            print(r.area);
+            r.width = 3;
+            print(r.area);
+            r.height = r.height + 1;
+            let {area, height} = r;
+            print(area, height);",
+            expect![[r#"
+                2
+                6
+                height 3
+                6 2
+            "#]],
+        );
+    }
+
+    #[test]
+    fn interpret_rest_and_spread_arguments() {
+        check(
+            "func sum(...xs: [int]) -> int {
+              let total = 0;
+              for x in xs { total = total + x; }
+              ret total;
+            }
+            func describe(first: int, ...rest: [string]) { print(first, rest); }
+            let more = [3, 4];
+            print(sum(), sum(1, 2), sum(...more), sum(1, ...more, 5));
+            describe(1);
+            describe(2, \"a\", ...[\"b\", \"c\"]);",
+            expect![[r#"
+                0 3 7 13
+                1 []
+                2 [a,b,c]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn interpret_recursive_generators() {
+        check(
+            "func walk(depth: int) -> int {
+              yield depth;
+              if depth > 0 {
+                for n in walk(depth - 1) { yield n * 10; }
+              }
+            }
+            let g = walk(2);
+            print(to_list(walk(2)));
+            let n = g.next();
+            until n == null {
+              n = g.next();
+            }
+            print(n);",
+            expect![[r#"
+                [2,10,0]
+                null
+            "#]],
+        );
+    }
+
+    #[test]
+    fn interpret_operator_overloads() {
+        check(
+            "class Vec {
+              x: int;
+              y: int;
+              constructor(x: int, y: int) { this.x = x; this.y = y; }
+              op_add(other: Vec) -> Vec { ret Vec(this.x + other.x, this.y + other.y); }
+              op_sub(other: Vec) -> Vec { ret Vec(this.x - other.x, this.y - other.y); }
+              op_mul(scale: int) -> Vec { ret Vec(this.x * scale, this.y * scale); }
+              op_div(scale: int) -> Vec { ret Vec(this.x / scale, this.y / scale); }
+              op_eq(other: Vec) -> bool { ret this.x == other.x and this.y == other.y; }
+              op_lt(other: Vec) -> bool { ret this.x < other.x; }
+              op_lte(other: Vec) -> bool { ret this.x <= other.x; }
+              op_gt(other: Vec) -> bool { ret this.x > other.x; }
+              op_gte(other: Vec) -> bool { ret this.x >= other.x; }
+              op_index(i: int) -> int { ret if i == 0 { this.x } else { this.y }; }
+              to_string() -> string { ret \"(\" + this.x + \", \" + this.y + \")\"; }
+            }
+            let a = Vec(1, 2);
+            let b = Vec(3, 4);
+            print(a + b, b - a, a * 3, (a / 2).y);
+            let same: bool = a == Vec(1, 2);
+            print(same, a != Vec(1, 2), a == b, a != b);
+            print(a < b, a <= b, a > b, a >= b);
+            print(b[0], b[1]);",
+            expect![[r#"
+                (4, 6) (2, 2) (3, 6) 1
+                true false false true
+                true true false false
+                3 4
+            "#]],
+        );
+    }
+
+    #[test]
+    fn interpret_default_and_named_arguments() {
+        check(
+            "func greet(name: string, greeting = \"Hello\", end: string = \"!\") -> string {
+              ret greeting + \", \" + name + end;
+            }
+            func range(start: int, end: int = start + 3) -> [int] {
+              ret [start + i for i in [0, 1, 2, 3, 4] if start + i < end];
+            }
+            class Account {
+              balance: int;
+              constructor(balance = 10) { this.balance = balance; }
+            }
+            print(greet(\"a\"), greet(\"a\", \"Hi\"), greet(\"a\", end: \"?\"));
+            print(greet(end: \".\", greeting: \"Bye\", name: \"b\"));
+            print(range(1), range(1, end: 2));
+            print(Account().balance, Account(balance: 5).balance);
+            let g = greet;
+            print(g(\"c\", \"Hey\", \"...\"));",
+            expect![[r#"
+                Hello, a! Hi, a! Hello, a?
+                Bye, b.
+                [1,2,3] [1]
+                10 5
+                Hey, c...
+            "#]],
+        );
+    }
+
+    #[test]
+    fn interpret_struct_copies_lists() {
+        check(
+            "struct Bag { items: [int]; }
+            struct Shelf { bag: Bag; bags: [Bag]; }
+            let a = Bag([1, 2]);
+            let b = a;
+            b.items[0] = 9;
+            print(a.items, b.items);
+            let c = Bag([]);
+            c = a;
+            c.items[0] = 4;
+            let shelf = Shelf(a, [a]);
+            shelf.bag.items[1] = 5;
+            let other = shelf;
+            other.bag.items[0] = 6;
+            other.bags[0] = b;
+            print(a.items, c.items, shelf, other);",
+            expect![[r#"
+                [1,2] [9,2]
+                [1,2] [4,2] Shelf(bag: Bag(items: [1,5]), bags: [Bag(items: [1,2])]) Shelf(bag: Bag(items: [6,5]), bags: [Bag(items: [9,2])])
+            "#]],
+        );
+    }
+}
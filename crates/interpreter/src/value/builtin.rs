@@ -1,14 +1,14 @@
 use ast::{
-    expr::Expression,
+    expr::{Arg, Expr},
     ty::{type_compatible, Type, TypeExpression},
 };
 use meta::{Span, Spanned};
-use parser::{Sym, Symbol};
+use parser::{Sym, Symbol, Visibility};
 
 use crate::{
     env::{Environment, ScopeId},
     error::RuntimeError,
-    expr::eval_expr,
+    expr::eval_args,
 };
 
 use super::{Callable, Value};
@@ -26,6 +26,33 @@ impl Builtin {
         params: Vec<Type>,
         returns: Option<Type>,
         handler: fn(Vec<Value>, &mut Environment) -> Result<Value, RuntimeError>,
+    ) -> Self {
+        Self::with_signature(name, params, false, returns, handler)
+    }
+
+    // Variadic builtins take any number of 'rest' arguments after their params, which the handler
+    // receives after the other values instead of wrapped in a list
+    pub fn variadic(
+        name: String,
+        mut params: Vec<Type>,
+        rest: Type,
+        returns: Option<Type>,
+        handler: fn(Vec<Value>, &mut Environment) -> Result<Value, RuntimeError>,
+    ) -> Self {
+        params.push(Type::List(Box::new(TypeExpression {
+            ty: rest,
+            span: Span::garbage(),
+        })));
+
+        Self::with_signature(name, params, true, returns, handler)
+    }
+
+    fn with_signature(
+        name: String,
+        params: Vec<Type>,
+        variadic: bool,
+        returns: Option<Type>,
+        handler: fn(Vec<Value>, &mut Environment) -> Result<Value, RuntimeError>,
     ) -> Self {
         Self {
             name,
@@ -42,12 +69,16 @@ impl Builtin {
                     span: Span::garbage(),
                     ty: returns.unwrap_or(Type::Null),
                 }),
+                variadic,
             },
         }
     }
 
     pub fn as_symbol(&self) -> Symbol {
-        if let Type::Function { params, .. } = &self.ty {
+        if let Type::Function {
+            params, variadic, ..
+        } = &self.ty
+        {
             Symbol {
                 name: Spanned {
                     item: self.name.clone(),
@@ -55,8 +86,12 @@ impl Builtin {
                 },
                 sym: Sym::Function {
                     arity: params.len(),
+                    required: params.len() - usize::from(*variadic),
+                    params: vec![],
+                    overloads: vec![],
                 },
                 ty: self.ty.clone(),
+                vis: Visibility::Public,
             }
         } else {
             panic!("ICE: builtin type is not a function signature");
@@ -98,26 +133,55 @@ impl Callable for Builtin {
     fn call(
         &self,
         callee_span: Span,
-        args: Vec<Expression>,
+        args: Vec<Arg>,
         env: &mut Environment,
         scope: ScopeId,
     ) -> Result<Value, RuntimeError> {
-        if self.arity() != args.len() {
-            return Err(RuntimeError::ArityMismatch(
-                callee_span.source_id,
-                callee_span.into(),
-                self.arity(),
-                args.len(),
-            ));
-        }
+        if let Type::Function {
+            params, variadic, ..
+        } = &self.ty
+        {
+            let fixed = params.len() - usize::from(*variadic);
+            for (idx, arg) in args.iter().enumerate() {
+                // Builtin params don't have names
+                if let Some(name) = &arg.name {
+                    return Err(RuntimeError::UnknownParameter(
+                        name.span.source_id,
+                        name.span.into(),
+                        name.item.clone(),
+                    ));
+                }
 
-        if let Type::Function { params, .. } = &self.ty {
-            let mut vals = vec![];
-            for (param, arg) in params.iter().zip(args.into_iter()) {
-                if !type_compatible(&arg.ty, &param.ty) {
-                    todo!("runtime builtin type mismatch");
+                // Spread lists can't be checked until they are expanded
+                if let Expr::Spread(..) = arg.value.expr {
+                    break;
                 }
-                vals.push(eval_expr(arg, env, scope)?);
+
+                let param_ty = match params.get(idx.min(fixed)).map(|param| &param.ty) {
+                    Some(Type::List(rest)) if idx >= fixed => &rest.ty,
+                    Some(ty) => ty,
+                    None => break,
+                };
+
+                if !type_compatible(&arg.value.ty, param_ty) {
+                    return Err(RuntimeError::IncompatibleTypes(
+                        callee_span.source_id,
+                        callee_span.into(),
+                        param_ty.clone(),
+                        arg.value.span.into(),
+                        arg.value.ty.clone(),
+                    ));
+                }
+            }
+
+            let (vals, _) = eval_args(args, env, scope)?;
+            if vals.len() < fixed || (!variadic && vals.len() > fixed) {
+                return Err(RuntimeError::ArityMismatch(
+                    callee_span.source_id,
+                    callee_span.into(),
+                    fixed,
+                    vals.len(),
+                ));
             }
 
             let mut val = (self.handler)(vals, env)?;
@@ -1,11 +1,15 @@
 use std::collections::HashMap;
 
-use ast::{expr::Expression, stmt::Class as AstClass};
+use ast::{
+    expr::Arg,
+    stmt::{Class as AstClass, Stmt},
+};
 use meta::Span;
 
 use crate::{
     env::{Environment, ScopeId},
     error::RuntimeError,
+    expr::eval_expr,
 };
 
 use super::{Callable, Value};
@@ -15,6 +19,9 @@ pub struct Class {
     pub class: AstClass,
     pub methods: HashMap<String, Value>,
     pub statics: HashMap<String, Value>,
+    pub getters: HashMap<String, Value>,
+    pub setters: HashMap<String, Value>,
+    pub closure: ScopeId,
 }
 
 impl Class {
@@ -35,11 +42,12 @@ impl Callable for Class {
     fn call(
         &self,
         callee_span: Span,
-        args: Vec<Expression>,
+        args: Vec<Arg>,
         env: &mut Environment,
         scope: ScopeId,
     ) -> Result<Value, RuntimeError> {
-        if self.arity() != args.len() {
+        // The constructor checks its own arguments, which might have default values
+        if self.constructor().is_none() && !args.is_empty() {
             return Err(RuntimeError::ArityMismatch(
                 callee_span.source_id,
                 callee_span.into(),
@@ -49,6 +57,22 @@ impl Callable for Class {
         }
 
         let val = env.new_instance(self.clone(), Span::garbage());
+
+        // Every instance gets its own copy of the declared fields
+        for field in &self.class.fields {
+            if let Stmt::Variable { name, expr } = &field.stmt {
+                let field_val = match expr {
+                    Some(expr) => eval_expr(expr.clone(), env, self.closure)?,
+                    None => Value::null(),
+                };
+
+                env.get_instance(val.as_instance()?)?
+                    .set_property(name.name.item.clone(), field_val)?;
+            } else {
+                panic!("ICE: class fields must be Stmt::Variable");
+            }
+        }
+
         let instance = env.get_instance(val.as_instance()?)?;
         if let Ok(mut constructor) = instance.get_property("constructor") {
             // bind this and execute constructor
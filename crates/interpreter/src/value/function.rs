@@ -1,11 +1,15 @@
-use ast::{expr::Expression, stmt::Function as AstFunction, ty::type_compatible};
+use ast::{
+    expr::Arg,
+    stmt::{Function as AstFunction, Param},
+    ty::{type_compatible, Type},
+};
 use meta::Span;
 
 use crate::{
     env::{Environment, ScopeId},
     error::RuntimeError,
     eval_block,
-    expr::eval_expr,
+    expr::{eval_args, eval_expr, NamedValues},
 };
 
 use super::{Callable, Value};
@@ -24,42 +28,130 @@ impl Callable for Function {
     fn call(
         &self,
         callee_span: Span,
-        args: Vec<Expression>,
+        args: Vec<Arg>,
         env: &mut Environment,
         scope: ScopeId,
     ) -> Result<Value, RuntimeError> {
-        if self.arity() != args.len() {
-            return Err(RuntimeError::ArityMismatch(
-                callee_span.source_id,
-                callee_span.into(),
-                self.arity(),
-                args.len(),
-            ));
-        }
+        let (vals, named) = eval_args(args, env, scope)?;
+        self.invoke(callee_span, vals, named, env)
+    }
+}
+
+impl Function {
+    // Calls the function with arguments that have already been evaluated, for example when the
+    // interpreter invokes an operator overload on an instance
+    pub fn call_with_values(
+        &self,
+        callee_span: Span,
+        vals: Vec<Value>,
+        env: &mut Environment,
+    ) -> Result<Value, RuntimeError> {
+        self.invoke(callee_span, vals, vec![], env)
+    }
 
+    fn invoke(
+        &self,
+        callee_span: Span,
+        vals: Vec<Value>,
+        named: NamedValues,
+        env: &mut Environment,
+    ) -> Result<Value, RuntimeError> {
         let new_scope = env.begin_scope(self.closure);
+        bind_args(&self.func.params, callee_span, vals, named, env, new_scope)?;
 
-        let params = &self.func.params;
-        for (param, arg) in params.iter().zip(args.into_iter()) {
-            // TODO try to move this to the parser
-            if !type_compatible(&param.ty, &arg.ty) {
-                return Err(RuntimeError::IncompatibleTypes(
-                    param.name.span.source_id,
-                    param.name.span.into(),
-                    param.ty.clone(),
-                    arg.span.into(),
-                    arg.ty,
-                ));
-            }
-            let val = eval_expr(arg, env, scope)?;
-            env.define(new_scope, param.name.item.clone(), val)?;
+        // Generators don't run their body until they are asked for a value
+        if self.func.generator {
+            let ty = match &self.func.ty.ty {
+                Type::Function { returns, .. } => returns.ty.clone(),
+                _ => panic!("ICE: function type is not Type::Function"),
+            };
+
+            return Ok(env.new_generator(*self.func.body.clone(), new_scope, ty, callee_span));
         }
 
         match eval_block(*self.func.body.clone(), env, new_scope) {
             Ok(()) => Ok(Value::null()),
-            Err(RuntimeError::EarlyReturn(val)) => Ok(val),
+            Err(RuntimeError::EarlyReturn(val)) => Ok(*val),
 
             Err(other) => Err(other),
         }
     }
 }
+
+// Positional arguments fill params in order, with any extras collected by the rest param, and
+// named ones go wherever their name says. Params that still don't have a value use their default.
+// Each param is defined in the given scope, so defaults can refer to the params before them
+pub(crate) fn bind_args(
+    params: &[Param],
+    callee_span: Span,
+    vals: Vec<Value>,
+    named: NamedValues,
+    env: &mut Environment,
+    scope: ScopeId,
+) -> Result<Vec<Value>, RuntimeError> {
+    let arg_count = vals.len() + named.len();
+
+    let mut slots: Vec<Option<Value>> = vec![None; params.len()];
+    let mut vals = vals.into_iter();
+    for (param, slot) in params.iter().zip(slots.iter_mut()) {
+        if param.rest {
+            let rest = vals.by_ref().collect();
+            *slot = Some(env.new_list(rest, param.ty.clone()));
+        } else {
+            *slot = vals.next();
+        }
+    }
+
+    if vals.len() > 0 {
+        return Err(RuntimeError::ArityMismatch(
+            callee_span.source_id,
+            callee_span.into(),
+            params.len(),
+            arg_count,
+        ));
+    }
+
+    for (name, val) in named {
+        match params.iter().position(|param| param.name.item == name.item) {
+            Some(idx) => slots[idx] = Some(val),
+            None => {
+                return Err(RuntimeError::UnknownParameter(
+                    name.span.source_id,
+                    name.span.into(),
+                    name.item,
+                ))
+            }
+        }
+    }
+
+    let mut bound = Vec::with_capacity(params.len());
+    for (param, slot) in params.iter().zip(slots) {
+        let val = match (slot, &param.default) {
+            (Some(val), _) => val,
+            (None, Some(default)) => eval_expr(default.clone(), env, scope)?,
+            (None, None) => {
+                return Err(RuntimeError::MissingArgument(
+                    callee_span.source_id,
+                    callee_span.into(),
+                    param.name.item.clone(),
+                ))
+            }
+        };
+
+        // TODO try to move this to the parser
+        if !type_compatible(&param.ty, &val.ty) {
+            return Err(RuntimeError::IncompatibleTypes(
+                param.name.span.source_id,
+                param.name.span.into(),
+                param.ty.clone(),
+                val.span.into(),
+                val.ty,
+            ));
+        }
+
+        env.define(scope, param.name.item.clone(), val.clone())?;
+        bound.push(val);
+    }
+
+    Ok(bound)
+}
@@ -0,0 +1,58 @@
+use ast::{expr::Expression, stmt::Statement};
+use meta::Span;
+
+use crate::env::ScopeId;
+
+use super::Iter;
+
+pub type GeneratorId = usize;
+
+// Generators run their body on an explicit stack of frames instead of the Rust stack, so they can
+// be suspended at a 'yield' and picked back up from the same place later on
+#[derive(Debug, Clone, PartialEq)]
+pub struct Generator {
+    pub id: GeneratorId,
+    pub span: Span,
+    pub frames: Vec<Frame>,
+    pub running: bool,
+}
+
+impl Generator {
+    pub fn new(id: GeneratorId, body: Statement, scope: ScopeId, span: Span) -> Self {
+        Self {
+            id,
+            span,
+            frames: vec![Frame::Block {
+                stmts: vec![body],
+                next: 0,
+                scope,
+            }],
+            running: false,
+        }
+    }
+}
+
+// The statements that can have a 'yield' inside of them, along with where they are at
+#[derive(Debug, Clone, PartialEq)]
+pub enum Frame {
+    Block {
+        stmts: Vec<Statement>,
+        next: usize,
+        scope: ScopeId,
+    },
+    // Loops also keep the scope of the iteration whose body is running, so it can be ended once
+    // the body finishes
+    Until {
+        cond: Expression,
+        body: Statement,
+        scope: ScopeId,
+        iteration: Option<ScopeId>,
+    },
+    For {
+        name: String,
+        iter: Iter,
+        body: Statement,
+        scope: ScopeId,
+        iteration: Option<ScopeId>,
+    },
+}
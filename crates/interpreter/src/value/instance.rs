@@ -36,6 +36,14 @@ impl Instance {
         }
     }
 
+    pub fn getter(&self, name: &str) -> Option<Value> {
+        self.class.getters.get(name).cloned()
+    }
+
+    pub fn setter(&self, name: &str) -> Option<Value> {
+        self.class.setters.get(name).cloned()
+    }
+
     pub fn set_property(&mut self, name: String, val: Value) -> Result<Value, RuntimeError> {
         self.fields.insert(name, val);
 
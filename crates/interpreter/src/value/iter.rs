@@ -0,0 +1,54 @@
+use ast::op::{ITER_METHOD, NEXT_METHOD};
+
+use crate::{env::Environment, error::RuntimeError, resume_generator};
+
+use super::{GeneratorId, ListId, Val, Value};
+
+// Walks over the values of a list, generator or iterable instance, for example in a for-in loop
+#[derive(Debug, Clone, PartialEq)]
+pub enum Iter {
+    List { id: ListId, next: usize },
+    Generator { id: GeneratorId },
+    // The instance returned by an 'iter' method, which is asked for values with 'next'
+    Instance { iterator: Value },
+}
+
+impl Iter {
+    pub fn new(val: &Value, env: &mut Environment) -> Result<Self, RuntimeError> {
+        match val.val {
+            Val::List { id } => Ok(Self::List { id, next: 0 }),
+            Val::Generator { id } => Ok(Self::Generator { id }),
+            Val::Instance { .. } => match val.call_method(env, ITER_METHOD, vec![], val.span)? {
+                Some(iterator) if matches!(iterator.val, Val::Instance { .. }) => {
+                    Ok(Self::Instance { iterator })
+                }
+                // 'iter' can also hand back a list or generator
+                Some(iterable) => Self::new(&iterable, env),
+                None => Err(not_iterable(val)),
+            },
+            _ => Err(not_iterable(val)),
+        }
+    }
+
+    pub fn next(&mut self, env: &mut Environment) -> Result<Option<Value>, RuntimeError> {
+        match self {
+            Self::List { id, next } => {
+                let val = env.get_list(*id).values().get(*next).cloned();
+                *next += 1;
+                Ok(val)
+            }
+            Self::Generator { id } => resume_generator(*id, env),
+            Self::Instance { iterator } => {
+                match iterator.call_method(env, NEXT_METHOD, vec![], iterator.span)? {
+                    Some(Value { val: Val::Null, .. }) => Ok(None),
+                    Some(val) => Ok(Some(val)),
+                    None => Err(not_iterable(iterator)),
+                }
+            }
+        }
+    }
+}
+
+fn not_iterable(val: &Value) -> RuntimeError {
+    RuntimeError::NotIterable(val.span.source_id, val.span.into(), val.ty.clone())
+}
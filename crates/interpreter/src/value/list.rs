@@ -16,16 +16,16 @@ impl List {
         Self { id, values }
     }
 
-    pub fn to_string(&self, env: &mut Environment) -> String {
-        format!(
+    pub fn to_string(&self, env: &mut Environment) -> Result<String, RuntimeError> {
+        Ok(format!(
             "[{}]",
             self.values
                 .clone()
                 .into_iter()
                 .map(|val| val.to_string(env))
-                .collect::<Vec<_>>()
+                .collect::<Result<Vec<_>, _>>()?
                 .join(",")
-        )
+        ))
     }
 
     pub fn len(&self) -> Value {
@@ -36,6 +36,10 @@ impl List {
         }
     }
 
+    pub fn values(&self) -> &[Value] {
+        &self.values
+    }
+
     pub fn extend_with(&mut self, other: Self) {
         self.values.extend(other.values);
     }
@@ -1,24 +1,34 @@
 mod builtin;
 mod class;
 mod function;
+mod generator;
 mod instance;
+mod iter;
 mod list;
+mod structs;
 mod val;
 
-use std::{cmp::Ordering, collections::HashMap};
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+};
 
 use ast::{
-    expr::{Expr, Expression},
-    op::Operator,
+    expr::{Arg, Expr, Expression},
+    op::{Operator, HASH_METHOD, TO_STRING_METHOD},
     stmt::{Statement, Stmt},
-    ty::{Type, TypeExpression},
+    ty::{type_compatible, Type, TypeExpression},
 };
 pub use builtin::*;
 pub use class::*;
 pub use function::*;
+pub use generator::*;
 pub use instance::*;
+pub use iter::*;
 pub use list::*;
-use meta::Span;
+use meta::{Span, Spanned};
+pub use structs::*;
 pub use val::*;
 
 use crate::{
@@ -32,7 +42,7 @@ pub trait Callable {
     fn call(
         &self,
         callee_span: Span,
-        args: Vec<Expression>,
+        args: Vec<Arg>,
         env: &mut Environment,
         scope: ScopeId,
     ) -> Result<Value, RuntimeError>;
@@ -100,7 +110,7 @@ impl Value {
         if let Stmt::Function(func) = stmt.stmt {
             Value {
                 ty: func.ty.ty.clone(),
-                val: Val::Function(Function { func, closure }),
+                val: Val::Function(Box::new(Function { func, closure })),
                 span: stmt.span,
             }
         } else {
@@ -128,29 +138,54 @@ impl Value {
                 }
             }
 
+            // Getters and setters are named after their property, so they get their own maps
+            let mut getters: HashMap<String, Value> = HashMap::default();
+            let mut setters: HashMap<String, Value> = HashMap::default();
+            for (accessors, stmts) in [
+                (&mut getters, &class.getters),
+                (&mut setters, &class.setters),
+            ] {
+                for stmt in stmts.clone() {
+                    if let Stmt::Function(func) = &stmt.stmt {
+                        let name = func.name.item.clone();
+                        accessors.insert(name, Value::from_function(stmt, scope));
+                    } else {
+                        panic!("ICE: class getters and setters must be Stmt::Function");
+                    }
+                }
+            }
+
             for static_stmt in class.statics.clone() {
-                if let Stmt::Variable { name, expr } = static_stmt.stmt {
-                    let name = name.name.item.clone();
+                match static_stmt.stmt {
+                    Stmt::Variable { name, expr } => {
+                        let name = name.name.item.clone();
 
-                    let mut val = Value::null();
+                        let mut val = Value::null();
 
-                    if let Some(expr) = expr {
-                        val = eval_expr(expr, env, scope)?;
-                    }
+                        if let Some(expr) = expr {
+                            val = eval_expr(expr, env, scope)?;
+                        }
 
-                    statics.insert(name, val);
-                } else {
-                    panic!("ICE: class statics must be Stmt::Variable");
+                        statics.insert(name, val);
+                    }
+                    Stmt::Function(ref func) => {
+                        let name = func.name.item.clone();
+                        statics.insert(name, Value::from_function(static_stmt, scope));
+                    }
+                    _ => panic!("ICE: class statics must be Stmt::Variable or Stmt::Function"),
                 }
             }
 
             Ok(Value {
                 ty: Type::Class(class.name.item.clone()),
-                val: Val::Class(Class {
+                val: Val::Class(Box::new(Class {
                     class,
                     methods,
                     statics,
-                }),
+                    getters,
+                    setters,
+                    closure: scope,
+                })),
                 span: stmt.span,
             })
         } else {
@@ -187,6 +222,13 @@ impl Value {
                 span,
                 ty: Type::String,
             }),
+            (Val::String(_), Val::Instance { .. }) | (Val::Instance { .. }, Val::String(_)) => {
+                Ok(Value {
+                    val: Val::String(format!("{}{}", self.to_string(env)?, rhs.to_string(env)?)),
+                    span,
+                    ty: Type::String,
+                })
+            }
             (Val::List { id: lhs_id }, Val::List { id: rhs_id }) => {
                 // TODO shouldn't have to clone entire env every time
                 let mut cloned_env = env.clone();
@@ -201,9 +243,9 @@ impl Value {
                 self.span.source_id,
                 op.span.into(),
                 self.span.into(),
-                self.ty.clone(),
+                Box::new(self.ty.clone()),
                 rhs.span.into(),
-                rhs.ty.clone(),
+                Box::new(rhs.ty.clone()),
             )),
         }
     }
@@ -231,9 +273,9 @@ impl Value {
                 self.span.source_id,
                 op.span.into(),
                 self.span.into(),
-                self.ty.clone(),
+                Box::new(self.ty.clone()),
                 rhs.span.into(),
-                rhs.ty.clone(),
+                Box::new(rhs.ty.clone()),
             )),
         }
     }
@@ -261,9 +303,9 @@ impl Value {
                 self.span.source_id,
                 op.span.into(),
                 self.span.into(),
-                self.ty.clone(),
+                Box::new(self.ty.clone()),
                 rhs.span.into(),
-                rhs.ty.clone(),
+                Box::new(rhs.ty.clone()),
             )),
         }
     }
@@ -295,9 +337,9 @@ impl Value {
                 self.span.source_id,
                 op.span.into(),
                 self.span.into(),
-                self.ty.clone(),
+                Box::new(self.ty.clone()),
                 rhs.span.into(),
-                rhs.ty.clone(),
+                Box::new(rhs.ty.clone()),
             )),
         }
     }
@@ -338,9 +380,25 @@ impl Value {
                 self.span.source_id,
                 op.span.into(),
                 self.span.into(),
-                self.ty.clone(),
+                Box::new(self.ty.clone()),
                 rhs.span.into(),
-                rhs.ty.clone(),
+                Box::new(rhs.ty.clone()),
+            )),
+        }
+    }
+
+    pub fn lt(&self, op: Operator, rhs: &Value) -> Result<Value, RuntimeError> {
+        let span = Span::combine(&[self.span, rhs.span]);
+
+        match (&self.val, &rhs.val) {
+            (Val::Int(lhs), Val::Int(rhs)) => Ok((lhs < rhs, span).into()),
+            _ => Err(RuntimeError::UnsupportedOperation(
+                self.span.source_id,
+                op.span.into(),
+                self.span.into(),
+                Box::new(self.ty.clone()),
+                rhs.span.into(),
+                Box::new(rhs.ty.clone()),
             )),
         }
     }
@@ -354,9 +412,9 @@ impl Value {
                 self.span.source_id,
                 op.span.into(),
                 self.span.into(),
-                self.ty.clone(),
+                Box::new(self.ty.clone()),
                 rhs.span.into(),
-                rhs.ty.clone(),
+                Box::new(rhs.ty.clone()),
             )),
         }
     }
@@ -370,9 +428,9 @@ impl Value {
                 self.span.source_id,
                 op.span.into(),
                 self.span.into(),
-                self.ty.clone(),
+                Box::new(self.ty.clone()),
                 rhs.span.into(),
-                rhs.ty.clone(),
+                Box::new(rhs.ty.clone()),
             )),
         }
     }
@@ -403,6 +461,58 @@ impl Value {
         })
     }
 
+    // Whether the value has the given type, which is what 'is' and 'as' check at runtime. Unlike
+    // the parser, this looks inside of lists
+    pub fn is(&self, ty: &Type, env: &mut Environment) -> bool {
+        match (ty, &self.val) {
+            (Type::Any, _) => true,
+            (Type::Union(tys), _) => tys.iter().any(|ty| self.is(&ty.ty, env)),
+            (Type::Int, Val::Int(..))
+            | (Type::Float, Val::Float(..))
+            | (Type::Bool, Val::Bool(..))
+            | (Type::String, Val::String(..))
+            | (Type::Null, Val::Null) => true,
+            (Type::List(elem), Val::List { id }) => env
+                .get_list(*id)
+                .values()
+                .to_vec()
+                .iter()
+                .all(|val| val.is(&elem.ty, env)),
+            // Generators are lazy, so the values they yield can't be checked up front
+            (Type::Generator(..), Val::Generator { .. }) => type_compatible(ty, &self.ty),
+            (Type::Function { .. }, Val::Function(..) | Val::Builtin(..)) => {
+                type_compatible(ty, &self.ty)
+            }
+            // Enum members are ints that remember which enum they belong to
+            (Type::Enum(..), Val::Int(..)) => self.ty == *ty,
+            (Type::Class(name), Val::Class(class)) => class.class.name.item == *name,
+            (Type::Instance(name), Val::Instance { name: class, .. }) => class == name,
+            (Type::Class(name), Val::Struct(strukt)) => strukt.strukt.name.item == *name,
+            (Type::Instance(name), Val::StructInstance { name: strukt, .. }) => strukt == name,
+            (Type::Tuple(tys), Val::Tuple(vals)) => {
+                tys.len() == vals.len() && vals.iter().zip(tys).all(|(val, ty)| val.is(&ty.ty, env))
+            }
+            _ => false,
+        }
+    }
+
+    pub fn get_field(&self, name: &Spanned<String>) -> Result<Value, RuntimeError> {
+        match &self.val {
+            Val::StructInstance { fields, .. } => fields
+                .iter()
+                .find(|(field, _)| *field == name.item)
+                .map(|(_, val)| val.clone())
+                .ok_or_else(|| {
+                    RuntimeError::UndefinedClassProperty(
+                        name.span.source_id,
+                        name.span.into(),
+                        name.item.clone(),
+                    )
+                }),
+            _ => panic!("ICE: get_field should only be called on structs"),
+        }
+    }
+
     pub fn as_bool(&self) -> Result<bool, RuntimeError> {
         match &self.val {
             Val::Bool(v) => Ok(*v),
@@ -439,14 +549,39 @@ impl Value {
         }
     }
 
+    pub fn as_list(&self) -> Result<ListId, RuntimeError> {
+        match &self.val {
+            Val::List { id } => Ok(*id),
+            _ => Err(RuntimeError::UnexpectedValueType(
+                self.span.source_id,
+                Type::List(Box::new(TypeExpression::any())),
+                format!("{}", self.val),
+                self.span.into(),
+            )),
+        }
+    }
+
+    pub fn as_tuple(&self) -> Result<&[Value], RuntimeError> {
+        match &self.val {
+            Val::Tuple(vals) => Ok(vals),
+            _ => Err(RuntimeError::UnexpectedValueType(
+                self.span.source_id,
+                Type::Tuple(vec![]),
+                format!("{}", self.val),
+                self.span.into(),
+            )),
+        }
+    }
+
     pub fn as_function(&self) -> Result<Function, RuntimeError> {
         match &self.val {
-            Val::Function(func) => Ok(func.clone()),
+            Val::Function(func) => Ok(*func.clone()),
             _ => Err(RuntimeError::UnexpectedValueType(
                 self.span.source_id,
                 Type::Function {
                     params: vec![TypeExpression::any()],
                     returns: Box::new(TypeExpression::any()),
+                    variadic: false,
                 },
                 format!("{}", self.val),
                 self.span.into(),
@@ -456,7 +591,7 @@ impl Value {
 
     pub fn as_class(&self) -> Result<Class, RuntimeError> {
         match &self.val {
-            Val::Class(class) => Ok(class.clone()),
+            Val::Class(class) => Ok(*class.clone()),
             _ => Err(RuntimeError::UnexpectedValueType(
                 self.span.source_id,
                 Type::Class(String::from("unkown")),
@@ -485,7 +620,77 @@ impl Value {
         }
     }
 
-    pub fn to_string(&self, env: &mut Environment) -> String {
+    // Calls the method `name` on this instance with already evaluated arguments. Returns None if
+    // the instance doesn't have a method with that name.
+    pub fn call_method(
+        &self,
+        env: &mut Environment,
+        name: &str,
+        args: Vec<Value>,
+        span: Span,
+    ) -> Result<Option<Value>, RuntimeError> {
+        let instance = env.get_instance(self.as_instance()?)?;
+
+        let mut method = match instance.get_property(name) {
+            Ok(method) if matches!(method.val, Val::Function(..)) => method,
+            _ => return Ok(None),
+        };
+
+        method.bind_this(env, self.clone())?;
+        method
+            .as_function()?
+            .call_with_values(span, args, env)
+            .map(Some)
+    }
+
+    pub fn to_string(&self, env: &mut Environment) -> Result<String, RuntimeError> {
+        // Instances can customize how they are printed with a 'to_string' method
+        if let Val::Instance { .. } = self.val {
+            if let Some(val) = self.call_method(env, TO_STRING_METHOD, vec![], self.span)? {
+                return val.to_string(env);
+            }
+        }
+
         self.val.to_string(env)
     }
+
+    pub fn hash(&self, env: &mut Environment) -> Result<i64, RuntimeError> {
+        // Instances can define their own 'hash', which has to agree with 'equals'
+        if let Val::Instance { .. } = self.val {
+            if let Some(val) = self.call_method(env, HASH_METHOD, vec![], self.span)? {
+                return val.as_int();
+            }
+        }
+
+        // Equal values hash the same, and the kind of value is hashed too so 1 and "1" differ
+        let mut hasher = DefaultHasher::new();
+        std::mem::discriminant(&self.val).hash(&mut hasher);
+        match &self.val {
+            Val::Bool(val) => val.hash(&mut hasher),
+            Val::Int(val) => val.hash(&mut hasher),
+            // -0.0 equals 0.0, and adding 0.0 turns it into 0.0
+            Val::Float(val) => (val + 0.0).to_bits().hash(&mut hasher),
+            Val::String(val) => val.hash(&mut hasher),
+            // Lists, instances and generators are only equal to themselves
+            Val::List { id } | Val::Generator { id } => id.hash(&mut hasher),
+            Val::Instance { id, .. } => id.hash(&mut hasher),
+            Val::StructInstance { name, fields } => {
+                name.hash(&mut hasher);
+                for (field, val) in fields {
+                    field.hash(&mut hasher);
+                    val.hash(env)?.hash(&mut hasher);
+                }
+            }
+            Val::Tuple(vals) => {
+                for val in vals {
+                    val.hash(env)?.hash(&mut hasher);
+                }
+            }
+            Val::Function(..) | Val::Builtin(..) | Val::Class(..) | Val::Struct(..) => {
+                self.to_string(env)?.hash(&mut hasher)
+            }
+            Val::Null => {}
+        }
+        Ok(hasher.finish() as i64)
+    }
 }
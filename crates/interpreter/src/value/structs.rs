@@ -0,0 +1,61 @@
+use ast::{expr::Arg, stmt::Struct as AstStruct, ty::Type};
+use meta::Span;
+
+use crate::{
+    env::{Environment, ScopeId},
+    error::RuntimeError,
+    expr::eval_args,
+};
+
+use super::{bind_args, Callable, Val, Value};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Struct {
+    pub strukt: AstStruct,
+    pub closure: ScopeId,
+}
+
+impl Callable for Struct {
+    fn arity(&self) -> usize {
+        self.strukt.fields.len()
+    }
+
+    fn call(
+        &self,
+        callee_span: Span,
+        args: Vec<Arg>,
+        env: &mut Environment,
+        scope: ScopeId,
+    ) -> Result<Value, RuntimeError> {
+        let (vals, named) = eval_args(args, env, scope)?;
+
+        // The constructor's scope only exists so that default values can use earlier fields
+        let new_scope = env.begin_scope(self.closure);
+        let vals = bind_args(
+            &self.strukt.fields,
+            callee_span,
+            vals,
+            named,
+            env,
+            new_scope,
+        )?;
+        env.delete_scope(new_scope);
+        let vals: Vec<_> = vals.into_iter().map(|val| env.copy_struct(val)).collect();
+
+        let name = self.strukt.name.item.clone();
+        Ok(Value {
+            val: Val::StructInstance {
+                name: name.clone(),
+                fields: self
+                    .strukt
+                    .fields
+                    .iter()
+                    .map(|field| field.name.item.clone())
+                    .zip(vals)
+                    .collect(),
+            },
+            span: callee_span,
+            ty: Type::Instance(name),
+        })
+    }
+}
@@ -1,8 +1,10 @@
 use std::cmp::Ordering;
 
-use crate::env::Environment;
+use crate::{env::Environment, error::RuntimeError};
 
-use super::{builtin::Builtin, class::Class, Function, InstanceId, ListId};
+use super::{
+    builtin::Builtin, class::Class, Function, GeneratorId, InstanceId, ListId, Struct, Value,
+};
 
 #[derive(Debug, Clone)]
 pub enum Val {
@@ -10,11 +12,27 @@ pub enum Val {
     Int(i64),
     Float(f64),
     String(String),
-    List { id: ListId },
-    Function(Function),
+    List {
+        id: ListId,
+    },
+    Function(Box<Function>),
     Builtin(Builtin),
-    Class(Class),
-    Instance { id: InstanceId, name: String },
+    Class(Box<Class>),
+    Instance {
+        id: InstanceId,
+        name: String,
+    },
+    Struct(Box<Struct>),
+    // Structs are copied whenever they are read, so their fields are stored inline like tuples
+    StructInstance {
+        name: String,
+        fields: Vec<(String, Value)>,
+    },
+    Generator {
+        id: GeneratorId,
+    },
+    // Tuples are immutable, so unlike lists they are stored inline
+    Tuple(Vec<Value>),
     Null,
 }
 
@@ -31,6 +49,10 @@ impl PartialOrd for Val {
                 Val::Builtin(..) => Some(Ordering::Less),
                 Val::Class(..) => Some(Ordering::Less),
                 Val::Instance { .. } => Some(Ordering::Less),
+                Val::Struct(..) => Some(Ordering::Less),
+                Val::StructInstance { .. } => Some(Ordering::Less),
+                Val::Generator { .. } => Some(Ordering::Less),
+                Val::Tuple(..) => Some(Ordering::Less),
                 Val::Null => Some(Ordering::Less),
             },
             (Val::Int(lhs), rhs) => match rhs {
@@ -43,6 +65,10 @@ impl PartialOrd for Val {
                 Val::Builtin(..) => Some(Ordering::Less),
                 Val::Class(..) => Some(Ordering::Less),
                 Val::Instance { .. } => Some(Ordering::Less),
+                Val::Struct(..) => Some(Ordering::Less),
+                Val::StructInstance { .. } => Some(Ordering::Less),
+                Val::Generator { .. } => Some(Ordering::Less),
+                Val::Tuple(..) => Some(Ordering::Less),
                 Val::Null => Some(Ordering::Less),
             },
             // float,
@@ -56,6 +82,10 @@ impl PartialOrd for Val {
                 Val::Builtin(..) => Some(Ordering::Less),
                 Val::Class(..) => Some(Ordering::Less),
                 Val::Instance { .. } => Some(Ordering::Less),
+                Val::Struct(..) => Some(Ordering::Less),
+                Val::StructInstance { .. } => Some(Ordering::Less),
+                Val::Generator { .. } => Some(Ordering::Less),
+                Val::Tuple(..) => Some(Ordering::Less),
                 Val::Null => Some(Ordering::Less),
             },
             // others
@@ -69,6 +99,10 @@ impl PartialOrd for Val {
                 Val::Builtin(..) => Some(Ordering::Less),
                 Val::Class(..) => Some(Ordering::Greater),
                 Val::Instance { id: rhs, .. } => lhs.partial_cmp(rhs),
+                Val::Struct(..) => Some(Ordering::Less),
+                Val::StructInstance { .. } => Some(Ordering::Less),
+                Val::Generator { .. } => Some(Ordering::Less),
+                Val::Tuple(..) => Some(Ordering::Less),
                 Val::Null => Some(Ordering::Less),
             },
             (Val::Null, rhs) => match rhs {
@@ -81,8 +115,38 @@ impl PartialOrd for Val {
                 Val::Builtin(..) => Some(Ordering::Less),
                 Val::Class(..) => Some(Ordering::Greater),
                 Val::Instance { .. } => Some(Ordering::Greater),
+                Val::Struct(..) => Some(Ordering::Greater),
+                Val::StructInstance { .. } => Some(Ordering::Greater),
+                Val::Generator { .. } => Some(Ordering::Greater),
+                Val::Tuple(..) => Some(Ordering::Greater),
                 Val::Null => Some(Ordering::Equal),
             },
+            (Val::Generator { id: lhs }, Val::Generator { id: rhs }) => lhs.partial_cmp(rhs),
+            (Val::Struct(lhs), Val::Struct(rhs)) => {
+                lhs.strukt.name.item.partial_cmp(&rhs.strukt.name.item)
+            }
+            // Structs of the same type compare field by field
+            (
+                Val::StructInstance {
+                    name: lhs_name,
+                    fields: lhs,
+                },
+                Val::StructInstance {
+                    name: rhs_name,
+                    fields: rhs,
+                },
+            ) => match lhs_name.partial_cmp(rhs_name) {
+                Some(Ordering::Equal) => lhs
+                    .iter()
+                    .map(|(_, v)| &v.val)
+                    .partial_cmp(rhs.iter().map(|(_, v)| &v.val)),
+                ordering => ordering,
+            },
+            // Tuples compare element by element
+            (Val::Tuple(lhs), Val::Tuple(rhs)) => lhs
+                .iter()
+                .map(|v| &v.val)
+                .partial_cmp(rhs.iter().map(|v| &v.val)),
             _ => todo!("PartialOrd for {:?} and {:?}", self, other),
         }
     }
@@ -91,7 +155,7 @@ impl PartialOrd for Val {
 impl PartialEq for Val {
     fn eq(&self, other: &Self) -> bool {
         print!("{:?}", self.partial_cmp(other));
-        self.partial_cmp(other).map_or(false, Ordering::is_eq)
+        self.partial_cmp(other).is_some_and(Ordering::is_eq)
     }
 }
 
@@ -110,6 +174,15 @@ impl std::fmt::Display for Val {
             Self::Builtin(builtin) => format!("{}", builtin),
             Self::Class(class) => class.class.name.item.to_string(),
             Self::Instance { id, name } => format!("{} instance (id {})", name.clone(), id),
+            Self::Generator { id } => format!("generator (id {})", id),
+            Self::Tuple(vals) => tuple_string(vals.iter().map(|v| format!("{}", v.val))),
+            Self::Struct(strukt) => strukt.strukt.name.item.to_string(),
+            Self::StructInstance { name, fields } => struct_string(
+                name,
+                fields
+                    .iter()
+                    .map(|(field, v)| (field, format!("{}", v.val))),
+            ),
         };
 
         f.write_str(msg.as_str())
@@ -117,8 +190,8 @@ impl std::fmt::Display for Val {
 }
 
 impl Val {
-    pub fn to_string(&self, env: &mut Environment) -> String {
-        match self {
+    pub fn to_string(&self, env: &mut Environment) -> Result<String, RuntimeError> {
+        Ok(match self {
             Self::Bool(v) => v.to_string(),
             Self::Int(v) => v.to_string(),
             Self::Float(v) => v.to_string(),
@@ -126,7 +199,7 @@ impl Val {
             Self::List { id } => {
                 let mut cloned_env = env.clone();
                 let list = cloned_env.get_list(*id);
-                list.to_string(env)
+                list.to_string(env)?
             }
             Self::Null => String::from("null"),
             Self::Function(func) => {
@@ -135,6 +208,39 @@ impl Val {
             Self::Builtin(builtin) => format!("{}", builtin),
             Self::Class(class) => class.class.name.item.to_string(),
             Self::Instance { id, name } => format!("{} instance (id {})", name.clone(), id),
-        }
+            Self::Generator { id } => format!("generator (id {})", id),
+            Self::Tuple(vals) => tuple_string(
+                vals.iter()
+                    .map(|v| v.to_string(env))
+                    .collect::<Result<Vec<_>, _>>()?
+                    .into_iter(),
+            ),
+            Self::Struct(strukt) => strukt.strukt.name.item.to_string(),
+            Self::StructInstance { name, fields } => struct_string(
+                name,
+                fields
+                    .iter()
+                    .map(|(field, v)| Ok((field, v.to_string(env)?)))
+                    .collect::<Result<Vec<_>, RuntimeError>>()?
+                    .into_iter(),
+            ),
+        })
+    }
+}
+
+fn tuple_string(vals: impl Iterator<Item = String>) -> String {
+    let vals = vals.collect::<Vec<_>>();
+    if vals.len() == 1 {
+        format!("({},)", vals[0])
+    } else {
+        format!("({})", vals.join(", "))
     }
 }
+
+// Ex: Point(x: 1, y: 2)
+fn struct_string<'a>(name: &str, fields: impl Iterator<Item = (&'a String, String)>) -> String {
+    let fields = fields
+        .map(|(field, val)| format!("{field}: {val}"))
+        .collect::<Vec<_>>();
+    format!("{}({})", name, fields.join(", "))
+}
@@ -57,6 +57,12 @@ pub enum TokenKind {
     LessEqual,
     #[token("->")]
     Arrow,
+    #[token("...")]
+    Ellipsis,
+    #[token("|>")]
+    Pipe,
+    #[token("|")]
+    Bar,
 
     // Literals
     #[regex("[A-Za-z_][A-Za-z0-9_]*")]
@@ -99,6 +105,22 @@ pub enum TokenKind {
     Static,
     #[token("enum")]
     Enum,
+    #[token("struct")]
+    Struct,
+    #[token("pub")]
+    Pub,
+    #[token("priv")]
+    Priv,
+    #[token("yield")]
+    Yield,
+    #[token("for")]
+    For,
+    #[token("in")]
+    In,
+    #[token("is")]
+    Is,
+    #[token("as")]
+    As,
 
     // Types
     #[token("int")]
@@ -154,6 +176,9 @@ impl fmt::Display for TokenKind {
             Self::Less => "<",
             Self::LessEqual => "<=",
             Self::Arrow => "->",
+            Self::Ellipsis => "...",
+            Self::Pipe => "|>",
+            Self::Bar => "|",
 
             // Literals
             Self::Ident => "ident",
@@ -177,6 +202,14 @@ impl fmt::Display for TokenKind {
             Self::Until => "until",
             Self::Static => "static",
             Self::Enum => "enum",
+            Self::Struct => "struct",
+            Self::Pub => "pub",
+            Self::Priv => "priv",
+            Self::Yield => "yield",
+            Self::For => "for",
+            Self::In => "in",
+            Self::Is => "is",
+            Self::As => "as",
 
             // Types
             Self::TypeInt => "int",
@@ -481,6 +514,21 @@ mod tests {
         check("->", TokenKind::Arrow);
     }
 
+    #[test]
+    fn lex_ellipsis() {
+        check("...", TokenKind::Ellipsis);
+    }
+
+    #[test]
+    fn lex_pipe() {
+        check("|>", TokenKind::Pipe);
+    }
+
+    #[test]
+    fn lex_bar() {
+        check("|", TokenKind::Bar);
+    }
+
     #[test]
     fn lex_static() {
         check("static", TokenKind::Static);
@@ -490,4 +538,44 @@ mod tests {
     fn lex_enum() {
         check("enum", TokenKind::Enum);
     }
+
+    #[test]
+    fn lex_struct() {
+        check("struct", TokenKind::Struct);
+    }
+
+    #[test]
+    fn lex_pub() {
+        check("pub", TokenKind::Pub);
+    }
+
+    #[test]
+    fn lex_priv() {
+        check("priv", TokenKind::Priv);
+    }
+
+    #[test]
+    fn lex_yield() {
+        check("yield", TokenKind::Yield);
+    }
+
+    #[test]
+    fn lex_for() {
+        check("for", TokenKind::For);
+    }
+
+    #[test]
+    fn lex_in() {
+        check("in", TokenKind::In);
+    }
+
+    #[test]
+    fn lex_is() {
+        check("is", TokenKind::Is);
+    }
+
+    #[test]
+    fn lex_as() {
+        check("as", TokenKind::As);
+    }
 }
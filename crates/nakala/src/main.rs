@@ -1,14 +1,20 @@
 use ast::ty::{Type, TypeExpression};
 use compiler::compile;
-use interpreter::{env::Environment, error::RuntimeError, interpret, Builtin, Val, Value};
+use interpreter::{env::Environment, error::RuntimeError, interpret, Builtin, Iter, Val, Value};
 use meta::Span;
-use miette::Result;
-use parser::{parse, source::Source, SymbolTable};
+use miette::{miette, Report, Result};
+use parser::{
+    lint::{lint, DeniedWarnings, Level, LintConfig},
+    parse,
+    source::Source,
+    Parse, SymbolTable,
+};
 use reedline::{DefaultPrompt, Reedline, Signal};
 use std::{fs::read_to_string, path::Path};
 
 fn main() -> Result<()> {
     let args = parse_arguments();
+    let lints = lint_config(&args.lints)?;
 
     let builtins = get_builtins();
     let symbols = builtins
@@ -21,16 +27,22 @@ fn main() -> Result<()> {
     let symtab = SymbolTable::new(symbols);
 
     if args.use_interpreter && args.input_files.is_empty() {
-        repl(args)
+        repl(args, lints)
     } else {
         if args.input_files.len() > 1 {
             todo!("multiple files are not supported yet.");
         }
 
         for source in args.input_files.into_iter() {
+            let lints = lints
+                .with_attributes(&source)
+                .map_err(|error| Report::new(error).with_source_code(source.clone()))?;
+
             let parse = parse(source.clone(), symtab.clone())
                 .map_err(|error| error.with_source_code(source.clone()))?;
 
+            report_warnings(&parse, &lints, &source)?;
+
             if args.show_parse {
                 println!("{:#?}", parse);
             }
@@ -47,9 +59,12 @@ fn main() -> Result<()> {
     }
 }
 
-fn repl(args: NakArguments) -> Result<()> {
+fn repl(args: NakArguments, mut lints: LintConfig) -> Result<()> {
+    // Every line is its own parse, so variables are usually used in later ones
+    lints.set("unused_variable", Level::Allow);
+
     let mut line_editor = Reedline::create();
-    let prompt = DefaultPrompt::default();
+    let prompt = DefaultPrompt;
 
     let builtins = get_builtins();
     let symbols = builtins
@@ -70,6 +85,8 @@ fn repl(args: NakArguments) -> Result<()> {
                 let parse = parse(source.clone(), symtab)
                     .map_err(|error| error.with_source_code(source.clone()))?;
 
+                report_warnings(&parse, &lints, &source)?;
+
                 if args.show_parse {
                     println!("{:#?}", parse);
                 }
@@ -86,6 +103,37 @@ fn repl(args: NakArguments) -> Result<()> {
     }
 }
 
+fn lint_config(levels: &[(Level, String)]) -> Result<LintConfig> {
+    let mut config = LintConfig::default();
+    for (level, name) in levels {
+        if !config.set(name, *level) {
+            return Err(miette!("Unknown lint '{}'", name));
+        }
+    }
+
+    Ok(config)
+}
+
+// Prints the warnings of the parse, and fails if any of them are denied
+fn report_warnings(parse: &Parse, lints: &LintConfig, source: &Source) -> Result<()> {
+    let (warnings, denied): (Vec<_>, Vec<_>) = lint(parse, lints)
+        .into_iter()
+        .partition(|warning| lints.level(warning.lint()) == Level::Warn);
+
+    for warning in warnings {
+        eprintln!(
+            "{:?}",
+            Report::new(warning).with_source_code(source.clone())
+        );
+    }
+
+    if denied.is_empty() {
+        Ok(())
+    } else {
+        Err(Report::new(DeniedWarnings { warnings: denied }).with_source_code(source.clone()))
+    }
+}
+
 fn get_builtins() -> Vec<Builtin> {
     let mut builtins = vec![];
 
@@ -93,16 +141,18 @@ fn get_builtins() -> Vec<Builtin> {
     fn print(vals: Vec<Value>, env: &mut Environment) -> Result<Value, RuntimeError> {
         println!(
             "{}",
-            vals.first()
-                .expect("arity mismatch didn't catch builtin")
-                .to_string(env)
+            vals.iter()
+                .map(|val| val.to_string(env))
+                .collect::<Result<Vec<_>, _>>()?
+                .join(" ")
         );
 
         Ok(Value::null())
     }
-    builtins.push(Builtin::new(
+    builtins.push(Builtin::variadic(
         String::from("print"),
-        vec![Type::Any],
+        vec![],
+        Type::Any,
         None,
         print,
     ));
@@ -132,14 +182,14 @@ fn get_builtins() -> Vec<Builtin> {
     fn str(vals: Vec<Value>, env: &mut Environment) -> Result<Value, RuntimeError> {
         let val = vals.first().expect("arity mismatch didn't catch builtin");
         Ok(Value {
-            val: Val::String(val.to_string(env)),
+            val: Val::String(val.to_string(env)?),
             span: val.span,
             ty: Type::String,
         })
     }
     builtins.push(Builtin::new(
         String::from("str"),
-        vec![Type::String],
+        vec![Type::Any],
         Some(Type::String),
         str,
     ));
@@ -175,6 +225,41 @@ fn get_builtins() -> Vec<Builtin> {
         len,
     ));
 
+    // hash
+    fn hash(vals: Vec<Value>, env: &mut Environment) -> Result<Value, RuntimeError> {
+        let val = vals.first().expect("arity mismatch didn't catch builtin");
+        Ok(Value {
+            val: Val::Int(val.hash(env)?),
+            span: val.span,
+            ty: Type::Int,
+        })
+    }
+    builtins.push(Builtin::new(
+        String::from("hash"),
+        vec![Type::Any],
+        Some(Type::Int),
+        hash,
+    ));
+
+    // to_list
+    fn to_list(vals: Vec<Value>, env: &mut Environment) -> Result<Value, RuntimeError> {
+        let val = vals.first().expect("arity mismatch didn't catch builtin");
+        let mut iter = Iter::new(val, env)?;
+
+        let mut values = vec![];
+        while let Some(val) = iter.next(env)? {
+            values.push(val);
+        }
+
+        Ok(env.new_list(values, Type::Any))
+    }
+    builtins.push(Builtin::new(
+        String::from("to_list"),
+        vec![Type::Any],
+        Some(Type::List(Box::new(TypeExpression::any()))),
+        to_list,
+    ));
+
     // chars
     fn chars(vals: Vec<Value>, env: &mut Environment) -> Result<Value, RuntimeError> {
         let val = vals.first().expect("arity mismatch didn't catch builtin");
@@ -212,6 +297,7 @@ struct NakArguments {
     use_interpreter: bool,
     use_compiler: bool,
     show_parse: bool,
+    lints: Vec<(Level, String)>,
 }
 
 fn parse_arguments() -> NakArguments {
@@ -222,6 +308,20 @@ fn parse_arguments() -> NakArguments {
     let show_parse = is_present(&["-p", "--show-parse"]);
     let use_compiler = is_present(&["-c", "--compile"]);
 
+    // Ex: '--deny unused_variable', or '-A all'
+    let lints = args
+        .windows(2)
+        .filter_map(|pair| {
+            let level = match pair[0].as_str() {
+                "-A" | "--allow" => Level::Allow,
+                "-W" | "--warn" => Level::Warn,
+                "-D" | "--deny" => Level::Deny,
+                _ => return None,
+            };
+            Some((level, pair[1].clone()))
+        })
+        .collect();
+
     let mut next_file_id = 0;
     let input_files = args
         .into_iter()
@@ -245,5 +345,6 @@ fn parse_arguments() -> NakArguments {
         use_interpreter: !use_compiler,
         use_compiler,
         show_parse,
+        lints,
     }
 }
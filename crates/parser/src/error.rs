@@ -42,9 +42,9 @@ pub enum ParseError {
         SourceId,
         #[label("This operation doesn't support these types")] SourceSpan,
         #[label("{3}")] SourceSpan,
-        Type,
+        Box<Type>,
         #[label("{5}")] SourceSpan,
-        Type,
+        Box<Type>,
     ),
 
     #[error("Unsupported unary operation")]
@@ -67,6 +67,17 @@ pub enum ParseError {
         String,
     ),
 
+    #[error("Type alias used as a value")]
+    #[diagnostic(
+        code(nak::type_alias_as_value),
+        help("Type aliases can only be used in type annotations, like 'let x: {2} = ...'")
+    )]
+    TypeAliasAsValue(
+        SourceId,
+        #[label("'{2}' is a type, not a value")] SourceSpan,
+        String,
+    ),
+
     #[error("Incompatible types")]
     #[diagnostic(code(nak::incompatible_types))]
     IncompatibleTypes(
@@ -84,6 +95,17 @@ pub enum ParseError {
     )]
     UnknownType(SourceId, #[label("This type is unknown")] SourceSpan),
 
+    #[error("Recursive type alias")]
+    #[diagnostic(
+        code(nak::recursive_type_alias),
+        help("Type aliases are replaced by their type, so {2} can't be used in its own type")
+    )]
+    RecursiveTypeAlias(
+        SourceId,
+        #[label("This refers to the alias")] SourceSpan,
+        String,
+    ),
+
     #[error("Uncallable expression")]
     #[diagnostic(
         code(nak::uncallable_expr),
@@ -100,7 +122,10 @@ pub enum ParseError {
     ),
 
     #[error("Function returns incompatible type")]
-    #[diagnostic(code(nak::incompatible_types))]
+    #[diagnostic(
+        code(nak::incompatible_types),
+        help("Every path through the function has to return a value of the return type")
+    )]
     FunctionHasIncompatibleReturnType(
         SourceId,
         #[label("Expected a type that is compatible with {2}")] SourceSpan,
@@ -132,6 +157,443 @@ pub enum ParseError {
         SourceId,
         String,
         #[label("This identifier")] SourceSpan,
-        #[label("has been previously defined here")] SourceSpan
-    )
+        #[label("has been previously defined here")] SourceSpan,
+    ),
+
+    #[error("Can't infer the return type of {2}")]
+    #[diagnostic(
+        code(nak::cyclic_return_type),
+        help("Functions that return the result of calling themselves need a return type annotation, ex: 'func {2}(...) -> int'")
+    )]
+    CyclicReturnType(
+        SourceId,
+        #[label("The return type depends on the result of this call")] SourceSpan,
+        String,
+    ),
+
+    #[error("Variable {2} might not have a value")]
+    #[diagnostic(
+        code(nak::uninitialized_variable),
+        help("Assign '{2}' a value when declaring it, or on every path before it is used")
+    )]
+    UninitializedVariable(
+        SourceId,
+        #[label("This is used before '{2}' is assigned")] SourceSpan,
+        String,
+        #[label("'{2}' is declared without a value here")] SourceSpan,
+    ),
+
+    #[error("No overload of {2} matches these arguments")]
+    #[diagnostic(
+        code(nak::no_matching_overload),
+        help("The arguments have types ({3})")
+    )]
+    NoMatchingOverload(SourceId, #[label("This call")] SourceSpan, String, String),
+
+    #[error("Ambiguous call to {2}")]
+    #[diagnostic(
+        code(nak::ambiguous_call),
+        help("More than one overload fits these arguments equally well, cast the arguments with 'as' to pick one")
+    )]
+    AmbiguousCall(
+        SourceId,
+        #[label("This call")] SourceSpan,
+        String,
+        #[label("could call this")] SourceSpan,
+        #[label("or this")] SourceSpan,
+    ),
+
+    #[error("Call to an overloaded method on an untyped object")]
+    #[diagnostic(
+        code(nak::untyped_overloaded_call),
+        help("Overloads are picked by the type of the object, so annotate it with its class")
+    )]
+    UntypedOverloadedCall(
+        SourceId,
+        #[label("Some class overloads '{2}', but the type of this object isn't known")] SourceSpan,
+        String,
+    ),
+
+    #[error("Invalid overload")]
+    #[diagnostic(
+        code(nak::invalid_overload),
+        help("Constructors, operator methods and protocol methods are called by name, so '{2}' can't be overloaded")
+    )]
+    InvalidOverload(SourceId, #[label("This overloads {2}")] SourceSpan, String),
+
+    #[error("Unknown lint {2}")]
+    #[diagnostic(
+        code(nak::unknown_lint),
+        help("The lints are unused_variable, shadowed_variable, unreachable_code and constant_comparison, or 'all' of them")
+    )]
+    UnknownLint(
+        SourceId,
+        #[label("This configures a lint that doesn't exist")] SourceSpan,
+        String,
+    ),
+
+    #[error("Duplicate enum value {2}")]
+    #[diagnostic(
+        code(nak::duplicate_enum_value),
+        help("Every member of an enum needs its own value, so 'name' can tell them apart")
+    )]
+    DuplicateEnumValue(
+        SourceId,
+        #[label("This member")] SourceSpan,
+        i64,
+        #[label("has the same value as this one")] SourceSpan,
+    ),
+
+    #[error("Reserved enum member {2}")]
+    #[diagnostic(
+        code(nak::reserved_enum_member),
+        help("Every enum gets 'name' and 'values' functions, so members can't use those names")
+    )]
+    ReservedEnumMember(
+        SourceId,
+        #[label("This member would hide the generated function")] SourceSpan,
+        String,
+    ),
+
+    #[error("Enum value out of range")]
+    #[diagnostic(
+        code(nak::enum_value_out_of_range),
+        help(
+            "Enum values are ints, so they must be between {} and {}",
+            i64::MIN,
+            i64::MAX
+        )
+    )]
+    EnumValueOutOfRange(
+        SourceId,
+        #[label("This value doesn't fit in an int")] SourceSpan,
+    ),
+
+    #[error("Unknown property")]
+    #[diagnostic(
+        code(nak::unknown_property),
+        help("Consider declaring it in the class body, like '{3}: any;'")
+    )]
+    UnknownProperty(
+        SourceId,
+        #[label("{2} has no field or method named '{3}'")] SourceSpan,
+        Type,
+        String,
+    ),
+
+    #[error("Unknown static property")]
+    #[diagnostic(
+        code(nak::unknown_static_property),
+        help("Consider declaring it in the class body, like 'static {3} = ...;'")
+    )]
+    UnknownStaticProperty(
+        SourceId,
+        #[label("{2} has no static property named '{3}'")] SourceSpan,
+        Type,
+        String,
+    ),
+
+    #[error("Can't use 'this' in a static method")]
+    #[diagnostic(
+        code(nak::this_in_static_method),
+        help("Static methods are called on the class itself, so there is no instance")
+    )]
+    ThisInStaticMethod(SourceId, #[label("There is no 'this' here")] SourceSpan),
+
+    #[error("Can't use 'this' in a field initializer")]
+    #[diagnostic(
+        code(nak::this_in_field_initializer),
+        help("Fields are initialized before the instance exists, so set it in the constructor instead")
+    )]
+    ThisInFieldInitializer(SourceId, #[label("There is no 'this' here")] SourceSpan),
+
+    #[error("Arity mismatch")]
+    #[diagnostic(code(nak::arity_mismatch))]
+    ArityMismatch(
+        SourceId,
+        #[label("This expects {2} arguments, but got {3}")] SourceSpan,
+        usize,
+        usize,
+    ),
+
+    #[error("Unknown field")]
+    #[diagnostic(
+        code(nak::unknown_field),
+        help("Only fields declared in the class body can be assigned to")
+    )]
+    UnknownField(
+        SourceId,
+        #[label("{2} has no field named '{3}'")] SourceSpan,
+        Type,
+        String,
+    ),
+
+    #[error("Invalid visibility modifier")]
+    #[diagnostic(
+        code(nak::invalid_visibility_modifier),
+        help("'pub' and 'priv' can only be used on class members")
+    )]
+    InvalidVisibilityModifier(
+        SourceId,
+        #[label("This modifier isn't allowed here")] SourceSpan,
+    ),
+
+    #[error("Private member")]
+    #[diagnostic(
+        code(nak::private_member),
+        help("Private members can only be used inside of the class body")
+    )]
+    PrivateMember(
+        SourceId,
+        #[label("'{3}' is private to {2}")] SourceSpan,
+        String,
+        String,
+        #[label("Declared private here")] SourceSpan,
+    ),
+
+    #[error("Invalid operator overload")]
+    #[diagnostic(
+        code(nak::invalid_operator_overload),
+        help("Operator methods take exactly one parameter, the right hand side operand")
+    )]
+    InvalidOperatorOverload(
+        SourceId,
+        #[label("This method expects {2} parameters")] SourceSpan,
+        usize,
+    ),
+
+    #[error("Invalid protocol method")]
+    #[diagnostic(
+        code(nak::invalid_protocol_method),
+        help("'{2}' is called implicitly by the interpreter, so it must be declared as '{3}'")
+    )]
+    InvalidProtocolMethod(
+        SourceId,
+        #[label("This method has the wrong signature")] SourceSpan,
+        String,
+        String,
+    ),
+
+    #[error("Missing hash method")]
+    #[diagnostic(
+        code(nak::missing_hash_method),
+        help("Equal instances have to hash the same, so declare 'hash() -> int' next to it")
+    )]
+    MissingHashMethod(
+        SourceId,
+        #[label("{2} declares 'equals' without 'hash'")] SourceSpan,
+        String,
+    ),
+
+    #[error("Unknown parameter")]
+    #[diagnostic(
+        code(nak::unknown_parameter),
+        help("Named arguments can only be used when calling declared functions, methods and constructors")
+    )]
+    UnknownParameter(
+        SourceId,
+        #[label("There is no parameter named '{2}'")] SourceSpan,
+        String,
+    ),
+
+    #[error("Duplicate argument")]
+    #[diagnostic(code(nak::duplicate_argument))]
+    DuplicateArgument(
+        SourceId,
+        #[label("'{2}' was given more than once")] SourceSpan,
+        String,
+    ),
+
+    #[error("Missing argument")]
+    #[diagnostic(code(nak::missing_argument))]
+    MissingArgument(
+        SourceId,
+        #[label("No argument was given for '{2}'")] SourceSpan,
+        String,
+    ),
+
+    #[error("Positional argument after named argument")]
+    #[diagnostic(
+        code(nak::positional_after_named),
+        help("Positional arguments must come before any named arguments")
+    )]
+    PositionalAfterNamed(SourceId, #[label("This argument has no name")] SourceSpan),
+
+    #[error("Missing default value")]
+    #[diagnostic(
+        code(nak::missing_default_value),
+        help("Parameters without default values must come before ones that have them")
+    )]
+    MissingDefaultValue(
+        SourceId,
+        #[label("'{2}' needs a default value")] SourceSpan,
+        String,
+    ),
+
+    #[error("Invalid rest parameter")]
+    #[diagnostic(
+        code(nak::invalid_rest_parameter),
+        help("A rest parameter has to be the last parameter, be a list, and can't have a default value")
+    )]
+    InvalidRestParameter(
+        SourceId,
+        #[label("This rest parameter is invalid")] SourceSpan,
+    ),
+
+    #[error("Invalid spread")]
+    #[diagnostic(code(nak::invalid_spread), help("Only lists can be spread"))]
+    InvalidSpread(
+        SourceId,
+        #[label("This has type {2}, which can't be spread")] SourceSpan,
+        Type,
+    ),
+
+    #[error("Yield outside of function")]
+    #[diagnostic(
+        code(nak::yield_outside_function),
+        help("Only functions can yield, which turns them into generators")
+    )]
+    YieldOutsideFunction(SourceId, #[label("Can't yield here")] SourceSpan),
+
+    #[error("Generator returns a value")]
+    #[diagnostic(
+        code(nak::generator_returns_value),
+        help("Generators produce values with 'yield', and 'ret;' finishes them")
+    )]
+    GeneratorReturnsValue(
+        SourceId,
+        #[label("This value would be thrown away")] SourceSpan,
+    ),
+
+    #[error("Not iterable")]
+    #[diagnostic(
+        code(nak::not_iterable),
+        help("Only lists, generators and instances with an 'iter' method can be looped over")
+    )]
+    NotIterable(
+        SourceId,
+        #[label("This has type {2}, which can't be looped over")] SourceSpan,
+        Type,
+    ),
+
+    #[error("Not indexable")]
+    #[diagnostic(
+        code(nak::not_indexable),
+        help("Only lists and instances with an 'op_index' method can be indexed")
+    )]
+    NotIndexable(
+        SourceId,
+        #[label("This has type {2}, which can't be indexed")] SourceSpan,
+        Type,
+    ),
+
+    #[error("Cannot destructure")]
+    #[diagnostic(
+        code(nak::cannot_destructure),
+        help("Lists can be destructured with 'let [a, b] = ...', and instances with 'let {{x, y}} = ...'")
+    )]
+    CannotDestructure(
+        SourceId,
+        #[label("This has type {2}, which doesn't match the pattern")] SourceSpan,
+        Type,
+    ),
+
+    #[error("Not a tuple")]
+    #[diagnostic(
+        code(nak::not_a_tuple),
+        help("Only tuples have numbered elements, like 'pair.0'")
+    )]
+    NotATuple(
+        SourceId,
+        #[label("This has type {2}, which isn't a tuple")] SourceSpan,
+        Type,
+    ),
+
+    #[error("Tuple index out of bounds")]
+    #[diagnostic(code(nak::tuple_index_out_of_bounds))]
+    TupleIndexOutOfBounds(
+        SourceId,
+        #[label("Tuple of type {2} has no element {3}")] SourceSpan,
+        Type,
+        usize,
+    ),
+
+    #[error("Tuple length mismatch")]
+    #[diagnostic(
+        code(nak::tuple_length_mismatch),
+        help("The pattern needs one binding per tuple element")
+    )]
+    TupleLengthMismatch(
+        SourceId,
+        #[label("Expected {2} bindings for a tuple of type {3}")] SourceSpan,
+        usize,
+        Type,
+    ),
+
+    #[error("Missing else branch")]
+    #[diagnostic(
+        code(nak::missing_else_branch),
+        help("An if expression needs an else branch, so that it has a value either way")
+    )]
+    MissingElseBranch(
+        SourceId,
+        #[label("This if expression has no else branch")] SourceSpan,
+    ),
+
+    #[error("Impossible cast")]
+    #[diagnostic(
+        code(nak::impossible_cast),
+        help("A cast only succeeds if the value could have the type, like a union containing it")
+    )]
+    ImpossibleCast(
+        SourceId,
+        #[label("A value of type {3} can never be {2}")] SourceSpan,
+        Type,
+        Type,
+    ),
+
+    #[error("Invalid getter")]
+    #[diagnostic(code(nak::invalid_getter), help("Getters can't take any parameters"))]
+    InvalidGetter(SourceId, #[label("This getter has parameters")] SourceSpan),
+
+    #[error("Invalid setter")]
+    #[diagnostic(
+        code(nak::invalid_setter),
+        help("Setters take exactly one parameter, which is the assigned value")
+    )]
+    InvalidSetter(
+        SourceId,
+        #[label("This setter doesn't have one parameter")] SourceSpan,
+    ),
+
+    #[error("Read only property")]
+    #[diagnostic(
+        code(nak::read_only_property),
+        help("Add a setter to the class to allow assignments")
+    )]
+    ReadOnlyProperty(
+        SourceId,
+        #[label("'{2}' has a getter, but no setter")] SourceSpan,
+        String,
+    ),
+
+    #[error("Write only property")]
+    #[diagnostic(
+        code(nak::write_only_property),
+        help("Add a getter to the class to allow reading it")
+    )]
+    WriteOnlyProperty(
+        SourceId,
+        #[label("'{2}' has a setter, but no getter")] SourceSpan,
+        String,
+    ),
+}
+
+// Every error found while parsing a source, which are reported together
+#[derive(Error, Diagnostic, Debug)]
+#[error("Found {} errors", .errors.len())]
+#[diagnostic(code(nak::multiple_errors))]
+pub struct ParseErrors {
+    #[related]
+    pub errors: Vec<ParseError>,
 }
@@ -0,0 +1,218 @@
+use ast::{
+    expr::{Expr, Expression},
+    stmt::{Function, Statement, Stmt},
+};
+use meta::{Span, Spanned};
+
+use crate::{
+    error::ParseError,
+    lint::always_returns,
+    walk::{walk_block, walk_expr, walk_function, walk_stmt, Declaration, Walker},
+};
+
+// Finds the reads of variables declared without a value that aren't assigned on every path to the
+// read. Ex: 'let x; if cond { x = 1; } print(x);'
+pub(crate) fn uninitialized_uses(stmts: &[Statement]) -> Vec<ParseError> {
+    let mut checker = Checker::default();
+    checker.visit_stmts(stmts);
+    checker.errors
+}
+
+struct Variable {
+    name: Spanned<String>,
+    assigned: bool,
+}
+
+struct Checker {
+    scopes: Vec<Vec<Variable>>,
+    errors: Vec<ParseError>,
+}
+
+impl Default for Checker {
+    fn default() -> Self {
+        Self {
+            scopes: vec![vec![]],
+            errors: vec![],
+        }
+    }
+}
+
+// Whether each variable in scope is assigned, in the order they were declared
+type State = Vec<bool>;
+
+// Code after a branch runs with only the variables that every branch assigned. Branches that
+// always return don't reach it, so they are left out.
+fn merge(lhs: Option<State>, rhs: Option<State>, before: State) -> State {
+    match (lhs, rhs) {
+        (Some(lhs), Some(rhs)) => lhs.iter().zip(rhs).map(|(lhs, rhs)| *lhs && rhs).collect(),
+        (Some(state), None) | (None, Some(state)) => state,
+        (None, None) => before,
+    }
+}
+
+impl Checker {
+    fn state(&self) -> State {
+        self.scopes
+            .iter()
+            .flatten()
+            .map(|var| var.assigned)
+            .collect()
+    }
+
+    fn set_state(&mut self, state: State) {
+        for (var, assigned) in self.scopes.iter_mut().flatten().zip(state) {
+            var.assigned = assigned;
+        }
+    }
+
+    fn lookup(&mut self, name: &str) -> Option<&mut Variable> {
+        self.scopes
+            .iter_mut()
+            .rev()
+            .flat_map(|scope| scope.iter_mut().rev())
+            .find(|var| var.name.item == name)
+    }
+}
+
+impl Walker for Checker {
+    fn begin_scope(&mut self) {
+        self.scopes.push(vec![]);
+    }
+
+    fn end_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn declare(&mut self, name: &Spanned<String>, declaration: Declaration) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push(Variable {
+                name: name.clone(),
+                assigned: !matches!(declaration, Declaration::Variable { initialized: false }),
+            });
+        }
+    }
+
+    fn read(&mut self, name: &str, span: Span) {
+        if let Some(var) = self.lookup(name).filter(|var| !var.assigned) {
+            // Only the first read is reported
+            var.assigned = true;
+            let declared = var.name.span;
+            self.errors.push(ParseError::UninitializedVariable(
+                span.source_id,
+                span.into(),
+                name.to_string(),
+                declared.into(),
+            ));
+        }
+    }
+
+    fn assign(&mut self, name: &str) {
+        if let Some(var) = self.lookup(name) {
+            var.assigned = true;
+        }
+    }
+
+    // Functions can be called once their variables are assigned, so their bodies only check
+    // their own variables
+    fn visit_function(&mut self, func: &Function) {
+        let mut checker = Checker::default();
+        walk_function(&mut checker, func);
+        self.errors.append(&mut checker.errors);
+    }
+
+    fn visit_stmt(&mut self, stmt: &Statement) {
+        match &stmt.stmt {
+            Stmt::If {
+                cond,
+                body,
+                else_branch,
+            } => {
+                self.visit_expr(cond);
+                let before = self.state();
+
+                walk_block(self, body);
+                let after_body = (!always_returns(body)).then(|| self.state());
+
+                self.set_state(before.clone());
+                if let Some(else_branch) = else_branch {
+                    walk_block(self, else_branch);
+                }
+                let after_else =
+                    (!else_branch.as_deref().is_some_and(always_returns)).then(|| self.state());
+
+                let after = merge(after_body, after_else, before);
+                self.set_state(after);
+            }
+            // The body might never run, so nothing it assigns counts after the loop
+            Stmt::Until { cond, body } => {
+                self.visit_expr(cond);
+                let before = self.state();
+                walk_block(self, body);
+                self.set_state(before);
+            }
+            Stmt::For {
+                binding,
+                iterable,
+                body,
+            } => {
+                self.visit_expr(iterable);
+                let before = self.state();
+                self.begin_scope();
+                self.declare(&binding.name, Declaration::Variable { initialized: true });
+                walk_block(self, body);
+                self.end_scope();
+                self.set_state(before);
+            }
+            _ => walk_stmt(self, stmt),
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &Expression) {
+        match &expr.expr {
+            // The right hand side might not run
+            Expr::Logical { lhs, rhs, .. } => {
+                self.visit_expr(lhs);
+                let before = self.state();
+                self.visit_expr(rhs);
+                self.set_state(before);
+            }
+            Expr::ListComprehension {
+                value,
+                binding,
+                iterable,
+                cond,
+            } => {
+                self.visit_expr(iterable);
+                let before = self.state();
+                self.begin_scope();
+                self.declare(&binding.name, Declaration::Variable { initialized: true });
+                if let Some(cond) = cond {
+                    self.visit_expr(cond);
+                }
+                self.visit_expr(value);
+                self.end_scope();
+                self.set_state(before);
+            }
+            Expr::If {
+                cond,
+                body,
+                else_branch,
+            } => {
+                self.visit_expr(cond);
+                let before = self.state();
+
+                self.visit_expr(body);
+                let after_body = self.state();
+
+                self.set_state(before.clone());
+                if let Some(else_branch) = else_branch {
+                    self.visit_expr(else_branch);
+                }
+
+                let after = merge(Some(after_body), Some(self.state()), before);
+                self.set_state(after);
+            }
+            _ => walk_expr(self, expr),
+        }
+    }
+}
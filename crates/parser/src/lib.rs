@@ -1,12 +1,15 @@
 pub mod error;
+mod init;
+pub mod lint;
 mod parser;
 pub mod source;
 mod symtab;
+mod walk;
 
 use crate::parser::Parser;
 use crate::source::Source;
 use ast::stmt::Statement;
-pub use symtab::{Sym, Symbol, SymbolTable};
+pub use symtab::{Sym, Symbol, SymbolTable, Visibility};
 
 #[derive(Debug, PartialEq)]
 pub struct Parse {
@@ -23,9 +26,9 @@ mod tests {
     use super::*;
     use expect_test::{expect, Expect};
 
-    impl<'a> Into<Source> for &'a str {
-        fn into(self) -> Source {
-            Source::new(0, String::from(self), "".into())
+    impl From<&str> for Source {
+        fn from(input: &str) -> Self {
+            Source::new(0, String::from(input), "".into())
         }
     }
 
@@ -37,6 +40,24 @@ mod tests {
         expected.assert_eq(result.as_str())
     }
 
+    // Lists the code and message of every error the input fails to parse with
+    fn check_errors(actual: &str, expected: Expect) {
+        let report = parse(actual.into(), SymbolTable::new(vec![])).unwrap_err();
+        let errors = match report.downcast_ref::<crate::error::ParseErrors>() {
+            Some(errors) => errors.errors.iter().collect(),
+            None => vec![report
+                .downcast_ref::<crate::error::ParseError>()
+                .expect("parse should only fail with parse errors")],
+        };
+
+        expected.assert_debug_eq(
+            &errors
+                .into_iter()
+                .map(|error| format!("{}: {}", miette::Diagnostic::code(error).unwrap(), error))
+                .collect::<Vec<_>>(),
+        );
+    }
+
     #[test]
     fn parse_string() {
         check(
@@ -257,6 +278,2082 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_class_field() {
+        check(
+            "class Foo { x: int = 1; }",
+            expect![[r#"
+                Parse {
+                    stmts: [
+                        Statement {
+                            stmt: Class(
+                                Class {
+                                    name: Spanned {
+                                        item: "Foo",
+                                        span: Span {
+                                            source_id: 0,
+                                            start: 6,
+                                            end: 9,
+                                        },
+                                    },
+                                    methods: [],
+                                    statics: [],
+                                    fields: [
+                                        Statement {
+                                            stmt: Variable {
+                                                name: Binding {
+                                                    name: Spanned {
+                                                        item: "x",
+                                                        span: Span {
+                                                            source_id: 0,
+                                                            start: 12,
+                                                            end: 13,
+                                                        },
+                                                    },
+                                                    ty: Int,
+                                                },
+                                                expr: Some(
+                                                    Expression {
+                                                        expr: Int(
+                                                            1,
+                                                        ),
+                                                        span: Span {
+                                                            source_id: 0,
+                                                            start: 21,
+                                                            end: 22,
+                                                        },
+                                                        ty: Int,
+                                                    },
+                                                ),
+                                            },
+                                            span: Span {
+                                                source_id: 0,
+                                                start: 12,
+                                                end: 23,
+                                            },
+                                        },
+                                    ],
+                                    getters: [],
+                                    setters: [],
+                                },
+                            ),
+                            span: Span {
+                                source_id: 0,
+                                start: 0,
+                                end: 25,
+                            },
+                        },
+                    ],
+                    symtab: SymbolTable {
+                        inner: [
+                            {
+                                "Foo": Symbol {
+                                    name: Spanned {
+                                        item: "Foo",
+                                        span: Span {
+                                            source_id: 0,
+                                            start: 6,
+                                            end: 9,
+                                        },
+                                    },
+                                    sym: Class {
+                                        methods: {},
+                                        statics: {},
+                                        fields: {
+                                            "x": Symbol {
+                                                name: Spanned {
+                                                    item: "x",
+                                                    span: Span {
+                                                        source_id: 0,
+                                                        start: 12,
+                                                        end: 13,
+                                                    },
+                                                },
+                                                sym: Variable,
+                                                ty: Int,
+                                                vis: Public,
+                                            },
+                                        },
+                                    },
+                                    ty: Class(
+                                        "Foo",
+                                    ),
+                                    vis: Private,
+                                },
+                            },
+                        ],
+                    },
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn parse_tuple_get() {
+        check(
+            "(1, \"a\").1;",
+            expect![[r#"
+            Parse {
+                stmts: [
+                    Statement {
+                        stmt: Expr(
+                            Expression {
+                                expr: TupleGet {
+                                    tuple: Expression {
+                                        expr: Tuple(
+                                            [
+                                                Expression {
+                                                    expr: Int(
+                                                        1,
+                                                    ),
+                                                    span: Span {
+                                                        source_id: 0,
+                                                        start: 1,
+                                                        end: 2,
+                                                    },
+                                                    ty: Int,
+                                                },
+                                                Expression {
+                                                    expr: String(
+                                                        "a",
+                                                    ),
+                                                    span: Span {
+                                                        source_id: 0,
+                                                        start: 4,
+                                                        end: 7,
+                                                    },
+                                                    ty: String,
+                                                },
+                                            ],
+                                        ),
+                                        span: Span {
+                                            source_id: 0,
+                                            start: 0,
+                                            end: 8,
+                                        },
+                                        ty: Tuple(
+                                            [
+                                                TypeExpression {
+                                                    ty: Int,
+                                                    span: Span {
+                                                        source_id: 0,
+                                                        start: 1,
+                                                        end: 2,
+                                                    },
+                                                },
+                                                TypeExpression {
+                                                    ty: String,
+                                                    span: Span {
+                                                        source_id: 0,
+                                                        start: 4,
+                                                        end: 7,
+                                                    },
+                                                },
+                                            ],
+                                        ),
+                                    },
+                                    index: Spanned {
+                                        item: 1,
+                                        span: Span {
+                                            source_id: 0,
+                                            start: 9,
+                                            end: 10,
+                                        },
+                                    },
+                                },
+                                span: Span {
+                                    source_id: 0,
+                                    start: 0,
+                                    end: 10,
+                                },
+                                ty: String,
+                            },
+                        ),
+                        span: Span {
+                            source_id: 0,
+                            start: 0,
+                            end: 11,
+                        },
+                    },
+                ],
+                symtab: SymbolTable {
+                    inner: [
+                        {},
+                    ],
+                },
+            }"#]],
+        );
+    }
+
+    #[test]
+    fn parse_if_expr() {
+        check(
+            "let x = if true { 1 } else { 2 };",
+            expect![[r#"
+            Parse {
+                stmts: [
+                    Statement {
+                        stmt: Variable {
+                            name: Binding {
+                                name: Spanned {
+                                    item: "x",
+                                    span: Span {
+                                        source_id: 0,
+                                        start: 4,
+                                        end: 5,
+                                    },
+                                },
+                                ty: Any,
+                            },
+                            expr: Some(
+                                Expression {
+                                    expr: If {
+                                        cond: Expression {
+                                            expr: Bool(
+                                                true,
+                                            ),
+                                            span: Span {
+                                                source_id: 0,
+                                                start: 11,
+                                                end: 15,
+                                            },
+                                            ty: Bool,
+                                        },
+                                        body: Expression {
+                                            expr: Block {
+                                                stmts: [],
+                                                value: Some(
+                                                    Expression {
+                                                        expr: Int(
+                                                            1,
+                                                        ),
+                                                        span: Span {
+                                                            source_id: 0,
+                                                            start: 18,
+                                                            end: 19,
+                                                        },
+                                                        ty: Int,
+                                                    },
+                                                ),
+                                            },
+                                            span: Span {
+                                                source_id: 0,
+                                                start: 16,
+                                                end: 21,
+                                            },
+                                            ty: Int,
+                                        },
+                                        else_branch: Some(
+                                            Expression {
+                                                expr: Block {
+                                                    stmts: [],
+                                                    value: Some(
+                                                        Expression {
+                                                            expr: Int(
+                                                                2,
+                                                            ),
+                                                            span: Span {
+                                                                source_id: 0,
+                                                                start: 29,
+                                                                end: 30,
+                                                            },
+                                                            ty: Int,
+                                                        },
+                                                    ),
+                                                },
+                                                span: Span {
+                                                    source_id: 0,
+                                                    start: 27,
+                                                    end: 32,
+                                                },
+                                                ty: Int,
+                                            },
+                                        ),
+                                    },
+                                    span: Span {
+                                        source_id: 0,
+                                        start: 8,
+                                        end: 32,
+                                    },
+                                    ty: Int,
+                                },
+                            ),
+                        },
+                        span: Span {
+                            source_id: 0,
+                            start: 0,
+                            end: 33,
+                        },
+                    },
+                ],
+                symtab: SymbolTable {
+                    inner: [
+                        {
+                            "x": Symbol {
+                                name: Spanned {
+                                    item: "x",
+                                    span: Span {
+                                        source_id: 0,
+                                        start: 4,
+                                        end: 5,
+                                    },
+                                },
+                                sym: Variable,
+                                ty: Int,
+                                vis: Private,
+                            },
+                        },
+                    ],
+                },
+            }"#]],
+        );
+    }
+
+    #[test]
+    fn parse_pipe() {
+        check(
+            "func f(x: int) {} 1 |> f;",
+            expect![[r#"
+            Parse {
+                stmts: [
+                    Statement {
+                        stmt: Function(
+                            Function {
+                                name: Spanned {
+                                    item: "f",
+                                    span: Span {
+                                        source_id: 0,
+                                        start: 5,
+                                        end: 6,
+                                    },
+                                },
+                                params: [
+                                    Param {
+                                        name: Spanned {
+                                            item: "x",
+                                            span: Span {
+                                                source_id: 0,
+                                                start: 7,
+                                                end: 8,
+                                            },
+                                        },
+                                        ty: Int,
+                                        default: None,
+                                        rest: false,
+                                    },
+                                ],
+                                body: Statement {
+                                    stmt: Block(
+                                        [],
+                                    ),
+                                    span: Span {
+                                        source_id: 0,
+                                        start: 15,
+                                        end: 17,
+                                    },
+                                },
+                                ty: TypeExpression {
+                                    ty: Function {
+                                        params: [
+                                            TypeExpression {
+                                                ty: Int,
+                                                span: Span {
+                                                    source_id: 0,
+                                                    start: 7,
+                                                    end: 8,
+                                                },
+                                            },
+                                        ],
+                                        returns: TypeExpression {
+                                            ty: Null,
+                                            span: Span {
+                                                source_id: 0,
+                                                start: 0,
+                                                end: 0,
+                                            },
+                                        },
+                                        variadic: false,
+                                    },
+                                    span: Span {
+                                        source_id: 0,
+                                        start: 5,
+                                        end: 6,
+                                    },
+                                },
+                                generator: false,
+                            },
+                        ),
+                        span: Span {
+                            source_id: 0,
+                            start: 0,
+                            end: 14,
+                        },
+                    },
+                    Statement {
+                        stmt: Expr(
+                            Expression {
+                                expr: Call {
+                                    callee: Expression {
+                                        expr: Variable(
+                                            "f",
+                                        ),
+                                        span: Span {
+                                            source_id: 0,
+                                            start: 23,
+                                            end: 24,
+                                        },
+                                        ty: Function {
+                                            params: [
+                                                TypeExpression {
+                                                    ty: Int,
+                                                    span: Span {
+                                                        source_id: 0,
+                                                        start: 7,
+                                                        end: 8,
+                                                    },
+                                                },
+                                            ],
+                                            returns: TypeExpression {
+                                                ty: Null,
+                                                span: Span {
+                                                    source_id: 0,
+                                                    start: 0,
+                                                    end: 0,
+                                                },
+                                            },
+                                            variadic: false,
+                                        },
+                                    },
+                                    paren: Span {
+                                        source_id: 0,
+                                        start: 23,
+                                        end: 24,
+                                    },
+                                    args: [
+                                        Arg {
+                                            name: None,
+                                            value: Expression {
+                                                expr: Int(
+                                                    1,
+                                                ),
+                                                span: Span {
+                                                    source_id: 0,
+                                                    start: 18,
+                                                    end: 19,
+                                                },
+                                                ty: Int,
+                                            },
+                                        },
+                                    ],
+                                },
+                                span: Span {
+                                    source_id: 0,
+                                    start: 18,
+                                    end: 24,
+                                },
+                                ty: Null,
+                            },
+                        ),
+                        span: Span {
+                            source_id: 0,
+                            start: 18,
+                            end: 25,
+                        },
+                    },
+                ],
+                symtab: SymbolTable {
+                    inner: [
+                        {
+                            "f": Symbol {
+                                name: Spanned {
+                                    item: "f",
+                                    span: Span {
+                                        source_id: 0,
+                                        start: 5,
+                                        end: 6,
+                                    },
+                                },
+                                sym: Function {
+                                    arity: 1,
+                                    required: 1,
+                                    params: [
+                                        "x",
+                                    ],
+                                    overloads: [],
+                                },
+                                ty: Function {
+                                    params: [
+                                        TypeExpression {
+                                            ty: Int,
+                                            span: Span {
+                                                source_id: 0,
+                                                start: 7,
+                                                end: 8,
+                                            },
+                                        },
+                                    ],
+                                    returns: TypeExpression {
+                                        ty: Null,
+                                        span: Span {
+                                            source_id: 0,
+                                            start: 0,
+                                            end: 0,
+                                        },
+                                    },
+                                    variadic: false,
+                                },
+                                vis: Private,
+                            },
+                        },
+                    ],
+                },
+            }"#]],
+        );
+
+        // The piped value goes to the outermost call on the right hand side
+        check(
+            "func make() -> any {} 5 |> make().add(1);",
+            expect![[r#"
+                Parse {
+                    stmts: [
+                        Statement {
+                            stmt: Function(
+                                Function {
+                                    name: Spanned {
+                                        item: "make",
+                                        span: Span {
+                                            source_id: 0,
+                                            start: 5,
+                                            end: 9,
+                                        },
+                                    },
+                                    params: [],
+                                    body: Statement {
+                                        stmt: Block(
+                                            [],
+                                        ),
+                                        span: Span {
+                                            source_id: 0,
+                                            start: 19,
+                                            end: 21,
+                                        },
+                                    },
+                                    ty: TypeExpression {
+                                        ty: Function {
+                                            params: [],
+                                            returns: TypeExpression {
+                                                ty: Any,
+                                                span: Span {
+                                                    source_id: 0,
+                                                    start: 15,
+                                                    end: 18,
+                                                },
+                                            },
+                                            variadic: false,
+                                        },
+                                        span: Span {
+                                            source_id: 0,
+                                            start: 5,
+                                            end: 9,
+                                        },
+                                    },
+                                    generator: false,
+                                },
+                            ),
+                            span: Span {
+                                source_id: 0,
+                                start: 0,
+                                end: 11,
+                            },
+                        },
+                        Statement {
+                            stmt: Expr(
+                                Expression {
+                                    expr: Call {
+                                        callee: Expression {
+                                            expr: Get {
+                                                object: Expression {
+                                                    expr: Call {
+                                                        callee: Expression {
+                                                            expr: Variable(
+                                                                "make",
+                                                            ),
+                                                            span: Span {
+                                                                source_id: 0,
+                                                                start: 27,
+                                                                end: 31,
+                                                            },
+                                                            ty: Function {
+                                                                params: [],
+                                                                returns: TypeExpression {
+                                                                    ty: Any,
+                                                                    span: Span {
+                                                                        source_id: 0,
+                                                                        start: 15,
+                                                                        end: 18,
+                                                                    },
+                                                                },
+                                                                variadic: false,
+                                                            },
+                                                        },
+                                                        paren: Span {
+                                                            source_id: 0,
+                                                            start: 32,
+                                                            end: 33,
+                                                        },
+                                                        args: [],
+                                                    },
+                                                    span: Span {
+                                                        source_id: 0,
+                                                        start: 27,
+                                                        end: 33,
+                                                    },
+                                                    ty: Any,
+                                                },
+                                                name: Spanned {
+                                                    item: "add",
+                                                    span: Span {
+                                                        source_id: 0,
+                                                        start: 34,
+                                                        end: 37,
+                                                    },
+                                                },
+                                            },
+                                            span: Span {
+                                                source_id: 0,
+                                                start: 27,
+                                                end: 37,
+                                            },
+                                            ty: Any,
+                                        },
+                                        paren: Span {
+                                            source_id: 0,
+                                            start: 39,
+                                            end: 40,
+                                        },
+                                        args: [
+                                            Arg {
+                                                name: None,
+                                                value: Expression {
+                                                    expr: Int(
+                                                        5,
+                                                    ),
+                                                    span: Span {
+                                                        source_id: 0,
+                                                        start: 22,
+                                                        end: 23,
+                                                    },
+                                                    ty: Int,
+                                                },
+                                            },
+                                            Arg {
+                                                name: None,
+                                                value: Expression {
+                                                    expr: Int(
+                                                        1,
+                                                    ),
+                                                    span: Span {
+                                                        source_id: 0,
+                                                        start: 38,
+                                                        end: 39,
+                                                    },
+                                                    ty: Int,
+                                                },
+                                            },
+                                        ],
+                                    },
+                                    span: Span {
+                                        source_id: 0,
+                                        start: 22,
+                                        end: 40,
+                                    },
+                                    ty: Any,
+                                },
+                            ),
+                            span: Span {
+                                source_id: 0,
+                                start: 22,
+                                end: 41,
+                            },
+                        },
+                    ],
+                    symtab: SymbolTable {
+                        inner: [
+                            {
+                                "make": Symbol {
+                                    name: Spanned {
+                                        item: "make",
+                                        span: Span {
+                                            source_id: 0,
+                                            start: 5,
+                                            end: 9,
+                                        },
+                                    },
+                                    sym: Function {
+                                        arity: 0,
+                                        required: 0,
+                                        params: [],
+                                        overloads: [],
+                                    },
+                                    ty: Function {
+                                        params: [],
+                                        returns: TypeExpression {
+                                            ty: Any,
+                                            span: Span {
+                                                source_id: 0,
+                                                start: 15,
+                                                end: 18,
+                                            },
+                                        },
+                                        variadic: false,
+                                    },
+                                    vis: Private,
+                                },
+                            },
+                        ],
+                    },
+                }"#]],
+        );
+    }
+
+    #[test]
+    fn parse_list_comprehension() {
+        check(
+            "[x for x in [1] if true];",
+            expect![[r#"
+            Parse {
+                stmts: [
+                    Statement {
+                        stmt: Expr(
+                            Expression {
+                                expr: ListComprehension {
+                                    value: Expression {
+                                        expr: Variable(
+                                            "x",
+                                        ),
+                                        span: Span {
+                                            source_id: 0,
+                                            start: 1,
+                                            end: 2,
+                                        },
+                                        ty: Int,
+                                    },
+                                    binding: Binding {
+                                        name: Spanned {
+                                            item: "x",
+                                            span: Span {
+                                                source_id: 0,
+                                                start: 7,
+                                                end: 8,
+                                            },
+                                        },
+                                        ty: Any,
+                                    },
+                                    iterable: Expression {
+                                        expr: List(
+                                            [
+                                                Expression {
+                                                    expr: Int(
+                                                        1,
+                                                    ),
+                                                    span: Span {
+                                                        source_id: 0,
+                                                        start: 13,
+                                                        end: 14,
+                                                    },
+                                                    ty: Int,
+                                                },
+                                            ],
+                                        ),
+                                        span: Span {
+                                            source_id: 0,
+                                            start: 12,
+                                            end: 15,
+                                        },
+                                        ty: List(
+                                            TypeExpression {
+                                                ty: Int,
+                                                span: Span {
+                                                    source_id: 0,
+                                                    start: 12,
+                                                    end: 15,
+                                                },
+                                            },
+                                        ),
+                                    },
+                                    cond: Some(
+                                        Expression {
+                                            expr: Bool(
+                                                true,
+                                            ),
+                                            span: Span {
+                                                source_id: 0,
+                                                start: 19,
+                                                end: 23,
+                                            },
+                                            ty: Bool,
+                                        },
+                                    ),
+                                },
+                                span: Span {
+                                    source_id: 0,
+                                    start: 0,
+                                    end: 24,
+                                },
+                                ty: List(
+                                    TypeExpression {
+                                        ty: Int,
+                                        span: Span {
+                                            source_id: 0,
+                                            start: 1,
+                                            end: 2,
+                                        },
+                                    },
+                                ),
+                            },
+                        ),
+                        span: Span {
+                            source_id: 0,
+                            start: 0,
+                            end: 25,
+                        },
+                    },
+                ],
+                symtab: SymbolTable {
+                    inner: [
+                        {},
+                    ],
+                },
+            }"#]],
+        );
+    }
+
+    #[test]
+    fn parse_union_type_alias() {
+        check(
+            "type Id = int | string;",
+            expect![[r#"
+            Parse {
+                stmts: [
+                    Statement {
+                        stmt: TypeAlias {
+                            name: Spanned {
+                                item: "Id",
+                                span: Span {
+                                    source_id: 0,
+                                    start: 5,
+                                    end: 7,
+                                },
+                            },
+                            ty: TypeExpression {
+                                ty: Union(
+                                    [
+                                        TypeExpression {
+                                            ty: Int,
+                                            span: Span {
+                                                source_id: 0,
+                                                start: 10,
+                                                end: 13,
+                                            },
+                                        },
+                                        TypeExpression {
+                                            ty: String,
+                                            span: Span {
+                                                source_id: 0,
+                                                start: 16,
+                                                end: 22,
+                                            },
+                                        },
+                                    ],
+                                ),
+                                span: Span {
+                                    source_id: 0,
+                                    start: 10,
+                                    end: 22,
+                                },
+                            },
+                        },
+                        span: Span {
+                            source_id: 0,
+                            start: 0,
+                            end: 23,
+                        },
+                    },
+                ],
+                symtab: SymbolTable {
+                    inner: [
+                        {
+                            "Id": Symbol {
+                                name: Spanned {
+                                    item: "Id",
+                                    span: Span {
+                                        source_id: 0,
+                                        start: 5,
+                                        end: 7,
+                                    },
+                                },
+                                sym: TypeAlias,
+                                ty: Union(
+                                    [
+                                        TypeExpression {
+                                            ty: Int,
+                                            span: Span {
+                                                source_id: 0,
+                                                start: 10,
+                                                end: 13,
+                                            },
+                                        },
+                                        TypeExpression {
+                                            ty: String,
+                                            span: Span {
+                                                source_id: 0,
+                                                start: 16,
+                                                end: 22,
+                                            },
+                                        },
+                                    ],
+                                ),
+                                vis: Private,
+                            },
+                        },
+                    ],
+                },
+            }"#]],
+        );
+    }
+
+    #[test]
+    fn parse_as() {
+        check(
+            "1 as int;",
+            expect![[r#"
+            Parse {
+                stmts: [
+                    Statement {
+                        stmt: Expr(
+                            Expression {
+                                expr: As {
+                                    expr: Expression {
+                                        expr: Int(
+                                            1,
+                                        ),
+                                        span: Span {
+                                            source_id: 0,
+                                            start: 0,
+                                            end: 1,
+                                        },
+                                        ty: Int,
+                                    },
+                                    ty: TypeExpression {
+                                        ty: Int,
+                                        span: Span {
+                                            source_id: 0,
+                                            start: 5,
+                                            end: 8,
+                                        },
+                                    },
+                                },
+                                span: Span {
+                                    source_id: 0,
+                                    start: 0,
+                                    end: 8,
+                                },
+                                ty: Int,
+                            },
+                        ),
+                        span: Span {
+                            source_id: 0,
+                            start: 0,
+                            end: 9,
+                        },
+                    },
+                ],
+                symtab: SymbolTable {
+                    inner: [
+                        {},
+                    ],
+                },
+            }"#]],
+        );
+    }
+
+    #[test]
+    fn parse_struct() {
+        check(
+            "struct P { x: int; }",
+            expect![[r#"
+            Parse {
+                stmts: [
+                    Statement {
+                        stmt: Struct(
+                            Struct {
+                                name: Spanned {
+                                    item: "P",
+                                    span: Span {
+                                        source_id: 0,
+                                        start: 7,
+                                        end: 8,
+                                    },
+                                },
+                                fields: [
+                                    Param {
+                                        name: Spanned {
+                                            item: "x",
+                                            span: Span {
+                                                source_id: 0,
+                                                start: 11,
+                                                end: 12,
+                                            },
+                                        },
+                                        ty: Int,
+                                        default: None,
+                                        rest: false,
+                                    },
+                                ],
+                            },
+                        ),
+                        span: Span {
+                            source_id: 0,
+                            start: 0,
+                            end: 20,
+                        },
+                    },
+                ],
+                symtab: SymbolTable {
+                    inner: [
+                        {
+                            "P": Symbol {
+                                name: Spanned {
+                                    item: "P",
+                                    span: Span {
+                                        source_id: 0,
+                                        start: 7,
+                                        end: 8,
+                                    },
+                                },
+                                sym: Class {
+                                    methods: {
+                                        "constructor": Symbol {
+                                            name: Spanned {
+                                                item: "constructor",
+                                                span: Span {
+                                                    source_id: 0,
+                                                    start: 7,
+                                                    end: 8,
+                                                },
+                                            },
+                                            sym: Function {
+                                                arity: 1,
+                                                required: 1,
+                                                params: [
+                                                    "x",
+                                                ],
+                                                overloads: [],
+                                            },
+                                            ty: Function {
+                                                params: [
+                                                    TypeExpression {
+                                                        ty: Int,
+                                                        span: Span {
+                                                            source_id: 0,
+                                                            start: 11,
+                                                            end: 12,
+                                                        },
+                                                    },
+                                                ],
+                                                returns: TypeExpression {
+                                                    ty: Instance(
+                                                        "P",
+                                                    ),
+                                                    span: Span {
+                                                        source_id: 0,
+                                                        start: 7,
+                                                        end: 8,
+                                                    },
+                                                },
+                                                variadic: false,
+                                            },
+                                            vis: Public,
+                                        },
+                                    },
+                                    statics: {},
+                                    fields: {
+                                        "x": Symbol {
+                                            name: Spanned {
+                                                item: "x",
+                                                span: Span {
+                                                    source_id: 0,
+                                                    start: 11,
+                                                    end: 12,
+                                                },
+                                            },
+                                            sym: Variable,
+                                            ty: Int,
+                                            vis: Public,
+                                        },
+                                    },
+                                },
+                                ty: Class(
+                                    "P",
+                                ),
+                                vis: Private,
+                            },
+                        },
+                    ],
+                },
+            }"#]],
+        );
+    }
+
+    #[test]
+    fn parse_class_getter() {
+        check(
+            "class A { get x() -> int { ret 1; } }",
+            expect![[r#"
+            Parse {
+                stmts: [
+                    Statement {
+                        stmt: Class(
+                            Class {
+                                name: Spanned {
+                                    item: "A",
+                                    span: Span {
+                                        source_id: 0,
+                                        start: 6,
+                                        end: 7,
+                                    },
+                                },
+                                methods: [],
+                                statics: [],
+                                fields: [],
+                                getters: [
+                                    Statement {
+                                        stmt: Function(
+                                            Function {
+                                                name: Spanned {
+                                                    item: "x",
+                                                    span: Span {
+                                                        source_id: 0,
+                                                        start: 14,
+                                                        end: 15,
+                                                    },
+                                                },
+                                                params: [],
+                                                body: Statement {
+                                                    stmt: Block(
+                                                        [
+                                                            Statement {
+                                                                stmt: Return(
+                                                                    Some(
+                                                                        Expression {
+                                                                            expr: Int(
+                                                                                1,
+                                                                            ),
+                                                                            span: Span {
+                                                                                source_id: 0,
+                                                                                start: 31,
+                                                                                end: 32,
+                                                                            },
+                                                                            ty: Int,
+                                                                        },
+                                                                    ),
+                                                                ),
+                                                                span: Span {
+                                                                    source_id: 0,
+                                                                    start: 27,
+                                                                    end: 33,
+                                                                },
+                                                            },
+                                                        ],
+                                                    ),
+                                                    span: Span {
+                                                        source_id: 0,
+                                                        start: 25,
+                                                        end: 35,
+                                                    },
+                                                },
+                                                ty: TypeExpression {
+                                                    ty: Function {
+                                                        params: [],
+                                                        returns: TypeExpression {
+                                                            ty: Int,
+                                                            span: Span {
+                                                                source_id: 0,
+                                                                start: 21,
+                                                                end: 24,
+                                                            },
+                                                        },
+                                                        variadic: false,
+                                                    },
+                                                    span: Span {
+                                                        source_id: 0,
+                                                        start: 14,
+                                                        end: 15,
+                                                    },
+                                                },
+                                                generator: false,
+                                            },
+                                        ),
+                                        span: Span {
+                                            source_id: 0,
+                                            start: 14,
+                                            end: 17,
+                                        },
+                                    },
+                                ],
+                                setters: [],
+                            },
+                        ),
+                        span: Span {
+                            source_id: 0,
+                            start: 0,
+                            end: 37,
+                        },
+                    },
+                ],
+                symtab: SymbolTable {
+                    inner: [
+                        {
+                            "A": Symbol {
+                                name: Spanned {
+                                    item: "A",
+                                    span: Span {
+                                        source_id: 0,
+                                        start: 6,
+                                        end: 7,
+                                    },
+                                },
+                                sym: Class {
+                                    methods: {},
+                                    statics: {},
+                                    fields: {
+                                        "x": Symbol {
+                                            name: Spanned {
+                                                item: "x",
+                                                span: Span {
+                                                    source_id: 0,
+                                                    start: 14,
+                                                    end: 15,
+                                                },
+                                            },
+                                            sym: Property {
+                                                get: true,
+                                                set: false,
+                                            },
+                                            ty: Int,
+                                            vis: Public,
+                                        },
+                                    },
+                                },
+                                ty: Class(
+                                    "A",
+                                ),
+                                vis: Private,
+                            },
+                        },
+                    ],
+                },
+            }"#]],
+        );
+    }
+
+    #[test]
+    fn parse_checks_properties() {
+        let rect = "class Rect {
+            priv w: int = 1;
+            get area() -> int { ret this.w * 2; }
+            set width(value: int) { this.w = value; }
+            get height() -> int { ret 2; }
+            set height(value: int) {}
+        }";
+        assert!(parse(
+            format!("{rect} let r = Rect(); r.width = 3; r.height = r.height + 1; let {{area, height}} = r;")
+                .as_str()
+                .into(),
+            SymbolTable::new(vec![]),
+        )
+        .is_ok());
+        check_errors(
+            &format!("{rect} Rect().area = 1;"),
+            expect![[r#"
+                [
+                    "nak::read_only_property: Read only property",
+                ]
+            "#]],
+        );
+        check_errors(
+            &format!("{rect} let w = Rect().width;"),
+            expect![[r#"
+                [
+                    "nak::write_only_property: Write only property",
+                ]
+            "#]],
+        );
+        check_errors(
+            &format!("{rect} Rect().area();"),
+            expect![[r#"
+                [
+                    "nak::uncallable_expr: Uncallable expression",
+                ]
+            "#]],
+        );
+        check_errors(
+            &format!("{rect} let {{width}} = Rect();"),
+            expect![[r#"
+                [
+                    "nak::write_only_property: Write only property",
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_checks_default_and_named_arguments() {
+        let greet = "func greet(name: string, greeting = \"Hello\", end: string = \"!\") {}";
+        assert!(parse(
+            format!(
+                "{greet} greet(\"a\"); greet(\"a\", \"b\"); greet(\"a\", end: \"?\");
+                greet(end: \"?\", name: \"a\"); let g = greet; g(\"a\", \"b\", \"c\");"
+            )
+            .as_str()
+            .into(),
+            SymbolTable::new(vec![]),
+        )
+        .is_ok());
+        check_errors(
+            &format!(
+                "{greet} greet(); greet(\"a\", volume: 1); greet(\"a\", name: \"b\");
+                greet(name: \"a\", \"b\"); greet(\"a\", greeting: 1);"
+            ),
+            expect![[r#"
+                [
+                    "nak::missing_argument: Missing argument",
+                    "nak::unknown_parameter: Unknown parameter",
+                    "nak::duplicate_argument: Duplicate argument",
+                    "nak::positional_after_named: Positional argument after named argument",
+                    "nak::incompatible_types: Incompatible types",
+                ]
+            "#]],
+        );
+        check_errors(
+            "func bad(a = 1, b) {}",
+            expect![[r#"
+                [
+                    "nak::missing_default_value: Missing default value",
+                ]
+            "#]],
+        );
+        // Function values don't know about defaults
+        check_errors(
+            &format!("{greet} let g = greet; g(\"a\");"),
+            expect![[r#"
+                [
+                    "nak::arity_mismatch: Arity mismatch",
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_checks_rest_and_spread_arguments() {
+        let funcs = "func sum(...xs: [int]) -> int { ret 0; }
+            func f(a: int) {}
+            func g(a: int, ...rest: [string]) {}";
+        assert!(parse(
+            format!("{funcs} sum(); sum(1, 2, ...[3]); f(...[1]); g(1, \"a\", ...[\"b\"]);")
+                .as_str()
+                .into(),
+            SymbolTable::new(vec![]),
+        )
+        .is_ok());
+        check_errors(
+            &format!("{funcs} sum(1, \"a\");"),
+            expect![[r#"
+                [
+                    "nak::incompatible_types: Incompatible types",
+                ]
+            "#]],
+        );
+        check_errors(
+            &format!("{funcs} sum(...[\"a\"]);"),
+            expect![[r#"
+                [
+                    "nak::incompatible_types: Incompatible types",
+                ]
+            "#]],
+        );
+        check_errors(
+            &format!("{funcs} f(...[\"a\"]);"),
+            expect![[r#"
+                [
+                    "nak::incompatible_types: Incompatible types",
+                ]
+            "#]],
+        );
+        check_errors(
+            &format!("{funcs} g(...[1]);"),
+            expect![[r#"
+                [
+                    "nak::incompatible_types: Incompatible types",
+                ]
+            "#]],
+        );
+        check_errors(
+            &format!("{funcs} f(1, 2);"),
+            expect![[r#"
+                [
+                    "nak::arity_mismatch: Arity mismatch",
+                ]
+            "#]],
+        );
+        check_errors(
+            &format!("{funcs} f(1, ...[2], 3);"),
+            expect![[r#"
+                [
+                    "nak::arity_mismatch: Arity mismatch",
+                ]
+            "#]],
+        );
+        check_errors(
+            &format!("{funcs} g();"),
+            expect![[r#"
+                [
+                    "nak::missing_argument: Missing argument",
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_rejects_invalid_operator_overload() {
+        check_errors(
+            "class Vec { op_add(lhs, rhs) { ret lhs; } }",
+            expect![[r#"
+                [
+                    "nak::invalid_operator_overload: Invalid operator overload",
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_checks_operator_overloads() {
+        check_errors(
+            "class A { op_eq(other: A) { ret 5; } }
+             class B { op_lt(other: B) -> int { ret 1; } }
+             class C {}
+             let c = C()[0];",
+            expect![[r#"
+                [
+                    "nak::invalid_protocol_method: Invalid protocol method",
+                    "nak::invalid_protocol_method: Invalid protocol method",
+                    "nak::not_indexable: Not indexable",
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_comparisons_are_bool() {
+        check_errors(
+            "class Money {
+                cents: int = 0;
+                op_lt(other: Money) -> bool { ret this.cents < other.cents; }
+                equals(other: Money) -> bool { ret this.cents == other.cents; }
+                hash() -> int { ret this.cents; }
+            }
+            let cheaper: bool = Money() < Money();
+            let n: int = 1 < 2.5;",
+            expect![[r#"
+                [
+                    "nak::incompatible_types: Incompatible types",
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_checks_fields_through_this() {
+        check_errors(
+            "class Point { x: int = 0; constructor() { this.x = \"oops\"; } }",
+            expect![[r#"
+                [
+                    "nak::incompatible_types: Incompatible types",
+                ]
+            "#]],
+        );
+        check_errors(
+            "class Point { constructor() { this.tpyo = 1; this.reset(); } reset() {} }",
+            expect![[r#"
+                [
+                    "nak::unknown_field: Unknown field",
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_rejects_this_in_static_method() {
+        check_errors(
+            "class Counter { count: int = 0; static make() { ret this.count; } }",
+            expect![[r#"
+                [
+                    "nak::this_in_static_method: Can't use 'this' in a static method",
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_checks_class_members() {
+        check_errors(
+            "class Point { x: int = 0; y: int = this.x; }",
+            expect![[r#"
+                [
+                    "nak::this_in_field_initializer: Can't use 'this' in a field initializer",
+                ]
+            "#]],
+        );
+        check_errors(
+            "class Point { x: int = 0; x() {} }",
+            expect![[r#"
+                [
+                    "nak::cannot_redeclare_symbol: Cannot redeclare symbol x",
+                ]
+            "#]],
+        );
+        check_errors(
+            "class Point { x() {} x: int = 0; }",
+            expect![[r#"
+                [
+                    "nak::cannot_redeclare_symbol: Cannot redeclare symbol x",
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_checks_visibility() {
+        let account = "class Account {
+            priv balance: int = 0;
+            pub owner: string = \"\";
+            priv valid(amount: int) -> bool { ret amount > 0; }
+            deposit(amount: int) { if this.valid(amount) { this.balance = this.balance + amount; } }
+            same(other: Account) -> bool { ret this.balance == other.balance; }
+        }";
+        assert!(parse(
+            format!("{account} Account().deposit(1); let o = Account().owner;")
+                .as_str()
+                .into(),
+            SymbolTable::new(vec![]),
+        )
+        .is_ok());
+        check_errors(
+            &format!("{account} let a = Account(); a.balance; a.valid(1); a.balance = 1;"),
+            expect![[r#"
+                [
+                    "nak::private_member: Private member",
+                    "nak::private_member: Private member",
+                    "nak::private_member: Private member",
+                ]
+            "#]],
+        );
+        check_errors(
+            &format!("{account} class Bank {{ peek(a: Account) -> int {{ ret a.balance; }} }}"),
+            expect![[r#"
+                [
+                    "nak::private_member: Private member",
+                ]
+            "#]],
+        );
+        check_errors(
+            "pub func f() {} priv let x = 1; func g() { pub let y = 1; }",
+            expect![[r#"
+                [
+                    "nak::invalid_visibility_modifier: Invalid visibility modifier",
+                    "nak::invalid_visibility_modifier: Invalid visibility modifier",
+                    "nak::invalid_visibility_modifier: Invalid visibility modifier",
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_rejects_generator_return_value() {
+        check_errors(
+            "func count() { yield 1; ret 2; }",
+            expect![[r#"
+                [
+                    "nak::generator_returns_value: Generator returns a value",
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_checks_generators() {
+        check_errors(
+            "func walk(depth: int) -> int {
+               yield depth;
+               if depth > 0 {
+                 for n in walk(depth - 1) { yield n; }
+               }
+             }
+             let g = walk(2);
+             let next: int | null = g.next();
+             let v: int = g.next();",
+            expect![[r#"
+                [
+                    "nak::incompatible_types: Incompatible types",
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_checks_type_aliases() {
+        assert!(parse(
+            "type Item = int | string; let xs: [Item] = [1, \"a\"]; let ys: [any] = [1, true];"
+                .into(),
+            SymbolTable::new(vec![]),
+        )
+        .is_ok());
+        check_errors(
+            "type A = int; type A = string;
+             class B {} type B = int;
+             type C = [C] | int;
+             let xs: [int | string] = [1, true];",
+            expect![[r#"
+                [
+                    "nak::cannot_redeclare_symbol: Cannot redeclare symbol A",
+                    "nak::cannot_redeclare_symbol: Cannot redeclare symbol B",
+                    "nak::recursive_type_alias: Recursive type alias",
+                    "nak::incompatible_types: Incompatible types",
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_rejects_type_alias_as_value() {
+        check_errors(
+            "type Num = int | float; let v = Num;",
+            expect![[r#"
+                [
+                    "nak::type_alias_as_value: Type alias used as a value",
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_requires_hash_with_equals() {
+        check_errors(
+            "class Id { equals(other: Id) -> bool { ret true; } }",
+            expect![[r#"
+                [
+                    "nak::missing_hash_method: Missing hash method",
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_iterator_methods_are_optional() {
+        check_errors(
+            "class Tape { next(steps: int) -> int { ret steps; } iter(n: int) { ret n; } }
+            for x in Tape() {}",
+            expect![[r#"
+                [
+                    "nak::not_iterable: Not iterable",
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_checks_enums() {
+        check_errors(
+            "enum Color { Red, Green } enum Size { Small } Color.Red == Size.Small;",
+            expect![[r#"
+                [
+                    "nak::unsupported_operation: Unsupported operation",
+                ]
+            "#]],
+        );
+        check_errors(
+            "enum Big { Max = 9223372036854775807, Next }",
+            expect![[r#"
+                [
+                    "nak::enum_value_out_of_range: Enum value out of range",
+                ]
+            "#]],
+        );
+        check_errors(
+            "enum Huge { A = 99999999999999999999 }",
+            expect![[r#"
+                [
+                    "nak::enum_value_out_of_range: Enum value out of range",
+                ]
+            "#]],
+        );
+        check_errors(
+            "enum Min { A = -9223372036854775808 } enum Dup { A = 1, B = 1 }",
+            expect![[r#"
+                [
+                    "nak::duplicate_enum_value: Duplicate enum value 1",
+                ]
+            "#]],
+        );
+        check_errors(
+            "enum Field { name, values } enum Kind { values = 2 }",
+            expect![[r#"
+                [
+                    "nak::reserved_enum_member: Reserved enum member name",
+                    "nak::reserved_enum_member: Reserved enum member values",
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_checks_overloads() {
+        let scaler = "class Scaler {
+            scale(by: int) -> int { ret by; }
+            scale(axis: string) -> string { ret axis; }
+        }";
+        let resolved = parse(
+            format!("{scaler} let s = Scaler(); s.scale(2); s.scale(\"x\");")
+                .as_str()
+                .into(),
+            SymbolTable::new(vec![]),
+        )
+        .unwrap();
+        let called: Vec<_> = resolved.stmts[2..]
+            .iter()
+            .map(|stmt| match &stmt.stmt {
+                ast::stmt::Stmt::Expr(ast::expr::Expression {
+                    expr: ast::expr::Expr::Call { callee, .. },
+                    ..
+                }) => match &callee.expr {
+                    ast::expr::Expr::Get { name, .. } => name.item.as_str(),
+                    _ => unreachable!(),
+                },
+                _ => unreachable!(),
+            })
+            .collect();
+        assert_eq!(called, ["scale", "scale(string)"]);
+        check_errors(
+            &format!("{scaler} Scaler().scale(true);"),
+            expect![[r#"
+                [
+                    "nak::no_matching_overload: No overload of scale matches these arguments",
+                ]
+            "#]],
+        );
+        check_errors(
+            "func pick(a: int, b: any) {} func pick(a: any, b: int) {} pick(1, 2);",
+            expect![[r#"
+                [
+                    "nak::ambiguous_call: Ambiguous call to pick",
+                ]
+            "#]],
+        );
+        check_errors(
+            &format!("func go(obj) {{ ret obj.scale(\"z\"); }} {scaler}"),
+            expect![[r#"
+                [
+                    "nak::untyped_overloaded_call: Call to an overloaded method on an untyped object",
+                ]
+            "#]],
+        );
+        assert!(parse(
+            format!("{scaler} func go(obj: Scaler) {{ ret obj.scale(\"z\"); }}")
+                .as_str()
+                .into(),
+            SymbolTable::new(vec![]),
+        )
+        .is_ok());
+        check_errors(
+            "func f(x: int) {} func f(y: int) {}
+             func g(x: int) -> int { ret x; } func g(x: int) -> string { ret \"\"; }
+             func h(x: int) {} func h(x: string) {} func h(y: string) {}
+             class A { m(x: int) {} m(y: int) {} }",
+            expect![[r#"
+                [
+                    "nak::cannot_redeclare_symbol: Cannot redeclare symbol f",
+                    "nak::cannot_redeclare_symbol: Cannot redeclare symbol g",
+                    "nak::cannot_redeclare_symbol: Cannot redeclare symbol h",
+                    "nak::cannot_redeclare_symbol: Cannot redeclare symbol m",
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_collects_errors() {
+        check_errors(
+            "let x: int = true; let y = ; func f() { 1 +; } f();",
+            expect![[r#"
+                [
+                    "nak::incompatible_types: Incompatible types",
+                    "nak::cant_parse_primary_expr: Expected an expression",
+                    "nak::cant_parse_primary_expr: Expected an expression",
+                ]
+            "#]],
+        );
+        check_errors(
+            "let n: int = \"s\"; let m = n + 1; let s = ; s; m;",
+            expect![[r#"
+                [
+                    "nak::incompatible_types: Incompatible types",
+                    "nak::cant_parse_primary_expr: Expected an expression",
+                ]
+            "#]],
+        );
+        check_errors(
+            "class A { op_add(a, b) {} get() {} } enum E { X = 1, Y = 1 } let x: int = true;",
+            expect![[r#"
+                [
+                    "nak::invalid_operator_overload: Invalid operator overload",
+                    "nak::duplicate_enum_value: Duplicate enum value 1",
+                    "nak::incompatible_types: Incompatible types",
+                ]
+            "#]],
+        );
+        check_errors(
+            "class A { m() { ret 1 +; } n() -> int { ret \"s\"; } o() {} } A().o();",
+            expect![[r#"
+                [
+                    "nak::cant_parse_primary_expr: Expected an expression",
+                    "nak::incompatible_types: Incompatible types",
+                ]
+            "#]],
+        );
+        check_errors(
+            "let [a, b] = 5; let {c} = 1 +; print(a, b, c);",
+            expect![[r#"
+                [
+                    "nak::cannot_destructure: Cannot destructure",
+                    "nak::cant_parse_primary_expr: Expected an expression",
+                ]
+            "#]],
+        );
+        check_errors(
+            "} } let x: int = true; }",
+            expect![[r#"
+                [
+                    "nak::cant_parse_primary_expr: Expected an expression",
+                    "nak::incompatible_types: Incompatible types",
+                    "nak::cant_parse_primary_expr: Expected an expression",
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_checks_every_return() {
+        check_errors(
+            "func a(x: int) -> int { if x > 0 { ret 1; } else { ret 2; } }
+            func b(x: int) -> int { if x > 0 { ret 1; } }
+            func c(x: int) -> int { if x > 0 { ret true; } ret 1; }
+            func d(x: int) -> int | null { if x > 0 { ret x; } }",
+            expect![[r#"
+                [
+                    "nak::incompatible_types: Function returns incompatible type",
+                    "nak::incompatible_types: Incompatible types",
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_checks_definite_assignment() {
+        check_errors(
+            "let a: int; let b: int; let c: int;
+            if true { a = 1; b = 1; } else { a = 2; }
+            until a > 2 { c = a; a = a + 1; }
+            a; b; c;",
+            expect![[r#"
+                [
+                    "nak::uninitialized_variable: Variable b might not have a value",
+                    "nak::uninitialized_variable: Variable c might not have a value",
+                ]
+            "#]],
+        );
+    }
+
+    #[test]
+    fn parse_infers_return_types() {
+        let parse = parse(
+            "func a(x: int) { ret x * 2; }
+            func b(x: int) { if x > 0 { ret \"positive\"; } }
+            func c() {}
+            func d(x: int) -> int { if x < 2 { ret 1; } ret x * d(x - 1); }"
+                .into(),
+            SymbolTable::new(vec![]),
+        )
+        .unwrap();
+
+        expect![[r#"
+            [
+                "int",
+                "string | null",
+                "null",
+                "int",
+            ]
+        "#]]
+        .assert_debug_eq(
+            &["a", "b", "c", "d"]
+                .iter()
+                .map(|name| match &parse.symtab.lookup(name).unwrap().ty {
+                    ast::ty::Type::Function { returns, .. } => returns.ty.to_string(),
+                    ty => panic!("{} is not a function", ty),
+                })
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    fn lint_warnings() {
+        let source: Source = "// @allow(unused_variable)
+            let x = 1; let y = 2;
+            func f(x: int) { ret x; y; }
+            let z = f(x); let w = 1 < 2;
+            let u = 1 == 1.0; let t = 2.5 != 2.5;
+            class Nan { equals(other: Nan) -> bool { ret false; } hash() -> int { ret 0; } }
+            let n = Nan(); let v = n == n;"
+            .into();
+        let config = lint::LintConfig::default()
+            .with_attributes(&source)
+            .unwrap();
+        let parse = parse(source, SymbolTable::new(vec![])).unwrap();
+
+        expect![[r#"
+            [
+                "nak::shadowed_variable",
+                "nak::unreachable_code",
+                "nak::constant_comparison",
+                "nak::constant_comparison",
+            ]
+        "#]]
+        .assert_debug_eq(
+            &lint::lint(&parse, &config)
+                .iter()
+                .map(|warning| miette::Diagnostic::code(warning).unwrap().to_string())
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    fn loop_variables_shadow() {
+        let source: Source =
+            "let x = 1; for x in [2] { x; } let ys = [x for x in [3]]; for x in [x] { x; } ys;"
+                .into();
+        let config = lint::LintConfig::default();
+        let parse = parse(source, SymbolTable::new(vec![])).unwrap();
+
+        expect![[r#"
+            [
+                "nak::shadowed_variable",
+                "nak::shadowed_variable",
+                "nak::shadowed_variable",
+            ]
+        "#]]
+        .assert_debug_eq(
+            &lint::lint(&parse, &config)
+                .iter()
+                .map(|warning| miette::Diagnostic::code(warning).unwrap().to_string())
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    fn parse_destructure_list() {
+        check(
+            "let [a] = [1];",
+            expect![[r#"
+            Parse {
+                stmts: [
+                    Statement {
+                        stmt: Destructure {
+                            pattern: List(
+                                [
+                                    Binding {
+                                        name: Spanned {
+                                            item: "a",
+                                            span: Span {
+                                                source_id: 0,
+                                                start: 5,
+                                                end: 6,
+                                            },
+                                        },
+                                        ty: Any,
+                                    },
+                                ],
+                            ),
+                            expr: Expression {
+                                expr: List(
+                                    [
+                                        Expression {
+                                            expr: Int(
+                                                1,
+                                            ),
+                                            span: Span {
+                                                source_id: 0,
+                                                start: 11,
+                                                end: 12,
+                                            },
+                                            ty: Int,
+                                        },
+                                    ],
+                                ),
+                                span: Span {
+                                    source_id: 0,
+                                    start: 10,
+                                    end: 13,
+                                },
+                                ty: List(
+                                    TypeExpression {
+                                        ty: Int,
+                                        span: Span {
+                                            source_id: 0,
+                                            start: 10,
+                                            end: 13,
+                                        },
+                                    },
+                                ),
+                            },
+                        },
+                        span: Span {
+                            source_id: 0,
+                            start: 0,
+                            end: 14,
+                        },
+                    },
+                ],
+                symtab: SymbolTable {
+                    inner: [
+                        {
+                            "a": Symbol {
+                                name: Spanned {
+                                    item: "a",
+                                    span: Span {
+                                        source_id: 0,
+                                        start: 5,
+                                        end: 6,
+                                    },
+                                },
+                                sym: Variable,
+                                ty: Int,
+                                vis: Private,
+                            },
+                        },
+                    ],
+                },
+            }"#]],
+        );
+    }
+
     //#[test]
     //fn parse_add() {
     //    check(
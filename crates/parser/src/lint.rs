@@ -0,0 +1,370 @@
+use std::collections::HashMap;
+
+use ast::{
+    expr::{Expr, Expression},
+    op::Op,
+    stmt::{Statement, Stmt},
+    ty::Type,
+};
+use meta::{SourceId, Span, Spanned};
+use miette::{Diagnostic, SourceSpan};
+use thiserror::Error;
+
+use crate::{
+    error::ParseError,
+    source::Source,
+    walk::{walk_expr, walk_stmts, Declaration, Walker},
+    Parse,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lint {
+    UnusedVariable,
+    ShadowedVariable,
+    UnreachableCode,
+    ConstantComparison,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 4] = [
+        Lint::UnusedVariable,
+        Lint::ShadowedVariable,
+        Lint::UnreachableCode,
+        Lint::ConstantComparison,
+    ];
+
+    // Used to allow or deny the lint, and the end of the code of its warnings
+    pub fn name(self) -> &'static str {
+        match self {
+            Lint::UnusedVariable => "unused_variable",
+            Lint::ShadowedVariable => "shadowed_variable",
+            Lint::UnreachableCode => "unreachable_code",
+            Lint::ConstantComparison => "constant_comparison",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|lint| lint.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Allow,
+    Warn,
+    Deny,
+}
+
+impl Level {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "allow" => Some(Level::Allow),
+            "warn" => Some(Level::Warn),
+            "deny" => Some(Level::Deny),
+            _ => None,
+        }
+    }
+}
+
+// Every lint warns unless it is configured otherwise
+#[derive(Debug, Clone, Default)]
+pub struct LintConfig {
+    levels: HashMap<Lint, Level>,
+}
+
+impl LintConfig {
+    pub fn level(&self, lint: Lint) -> Level {
+        self.levels.get(&lint).copied().unwrap_or(Level::Warn)
+    }
+
+    // 'all' sets the level of every lint. Returns false if there is no lint with the name.
+    pub fn set(&mut self, name: &str, level: Level) -> bool {
+        if name == "all" {
+            for lint in Lint::ALL {
+                self.levels.insert(lint, level);
+            }
+            return true;
+        }
+
+        Lint::from_name(name)
+            .map(|lint| self.levels.insert(lint, level))
+            .is_some()
+    }
+
+    // Sources can configure lints for themselves with comments like '// @allow(unused_variable)',
+    // which take precedence over the configuration they are applied to
+    pub fn with_attributes(&self, source: &Source) -> Result<Self, ParseError> {
+        let mut config = self.clone();
+        for comment in source.comments() {
+            if let Some((level, names)) = attribute(&comment.text) {
+                for name in names.split(',').map(str::trim) {
+                    if !config.set(name, level) {
+                        return Err(ParseError::UnknownLint(
+                            source.id,
+                            comment.span.into(),
+                            name.to_string(),
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+// Ex: '// @deny(shadowed_variable, unused_variable)'
+fn attribute(comment: &str) -> Option<(Level, &str)> {
+    let attribute = comment.trim_start_matches('/').trim().strip_prefix('@')?;
+    let (level, names) = attribute.strip_suffix(')')?.split_once('(')?;
+    Some((Level::from_name(level.trim())?, names))
+}
+
+#[derive(Error, Diagnostic, Debug)]
+pub enum Warning {
+    #[error("Unused variable")]
+    #[diagnostic(
+        code(nak::unused_variable),
+        severity(warning),
+        help("If this is intentional, prefix it with an underscore: '_{2}'")
+    )]
+    UnusedVariable(SourceId, #[label("'{2}' is never used")] SourceSpan, String),
+
+    #[error("Shadowed variable")]
+    #[diagnostic(
+        code(nak::shadowed_variable),
+        severity(warning),
+        help("Consider giving one of them a different name")
+    )]
+    ShadowedVariable(
+        SourceId,
+        #[label("This shadows '{3}'")] SourceSpan,
+        #[label("which was declared here")] SourceSpan,
+        String,
+    ),
+
+    #[error("Unreachable code")]
+    #[diagnostic(
+        code(nak::unreachable_code),
+        severity(warning),
+        help("Consider removing it")
+    )]
+    UnreachableCode(
+        SourceId,
+        #[label("This is never run")] SourceSpan,
+        #[label("because this always returns")] SourceSpan,
+    ),
+
+    #[error("Comparison is always {2}")]
+    #[diagnostic(code(nak::constant_comparison), severity(warning))]
+    ConstantComparison(SourceId, #[label("This is always {2}")] SourceSpan, bool),
+}
+
+impl Warning {
+    pub fn lint(&self) -> Lint {
+        match self {
+            Warning::UnusedVariable(..) => Lint::UnusedVariable,
+            Warning::ShadowedVariable(..) => Lint::ShadowedVariable,
+            Warning::UnreachableCode(..) => Lint::UnreachableCode,
+            Warning::ConstantComparison(..) => Lint::ConstantComparison,
+        }
+    }
+}
+
+// Denied warnings fail the parse just like errors
+#[derive(Error, Diagnostic, Debug)]
+#[error("Found {} denied warnings", .warnings.len())]
+#[diagnostic(
+    code(nak::denied_warnings),
+    help("Lints can be allowed with '--allow <lint>' or a '// @allow(<lint>)' comment")
+)]
+pub struct DeniedWarnings {
+    #[related]
+    pub warnings: Vec<Warning>,
+}
+
+// Finds the warnings of a parse, leaving out the ones for lints that are allowed
+pub fn lint(parse: &Parse, config: &LintConfig) -> Vec<Warning> {
+    let mut linter = Linter::default();
+    linter.visit_stmts(&parse.stmts);
+    linter.end_scope();
+
+    linter
+        .warnings
+        .into_iter()
+        .filter(|warning| config.level(warning.lint()) != Level::Allow)
+        .collect()
+}
+
+// Whether the statement returns no matter which branches are taken
+pub(crate) fn always_returns(stmt: &Statement) -> bool {
+    match &stmt.stmt {
+        Stmt::Return(..) => true,
+        Stmt::Block(stmts) => stmts.iter().any(always_returns),
+        Stmt::If {
+            body,
+            else_branch: Some(else_branch),
+            ..
+        } => always_returns(body) && always_returns(else_branch),
+        _ => false,
+    }
+}
+
+struct Variable {
+    name: Spanned<String>,
+    used: bool,
+}
+
+struct Linter {
+    scopes: Vec<Vec<Variable>>,
+    warnings: Vec<Warning>,
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Self {
+            scopes: vec![vec![]],
+            warnings: vec![],
+        }
+    }
+}
+
+impl Walker for Linter {
+    fn begin_scope(&mut self) {
+        self.scopes.push(vec![]);
+    }
+
+    fn end_scope(&mut self) {
+        for var in self.scopes.pop().unwrap_or_default() {
+            if !var.used && !var.name.item.starts_with('_') {
+                self.warnings.push(Warning::UnusedVariable(
+                    var.name.span.source_id,
+                    var.name.span.into(),
+                    var.name.item,
+                ));
+            }
+        }
+    }
+
+    // Parameters can go unused, since the signature of a function isn't always up to its body
+    fn declare(&mut self, name: &Spanned<String>, declaration: Declaration) {
+        let shadowed = self
+            .scopes
+            .iter()
+            .rev()
+            .skip(1)
+            .flatten()
+            .find(|var| var.name.item == name.item);
+
+        if let Some(shadowed) = shadowed {
+            self.warnings.push(Warning::ShadowedVariable(
+                name.span.source_id,
+                name.span.into(),
+                shadowed.name.span.into(),
+                name.item.clone(),
+            ));
+        }
+
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push(Variable {
+                name: name.clone(),
+                used: matches!(declaration, Declaration::Param),
+            });
+        }
+    }
+
+    fn read(&mut self, name: &str, _span: Span) {
+        if let Some(var) = self
+            .scopes
+            .iter_mut()
+            .rev()
+            .flat_map(|scope| scope.iter_mut().rev())
+            .find(|var| var.name.item == name)
+        {
+            var.used = true;
+        }
+    }
+
+    fn visit_stmts(&mut self, stmts: &[Statement]) {
+        if let Some(pos) = stmts.iter().position(always_returns) {
+            if let (Some(first), Some(last)) = (stmts.get(pos + 1), stmts.last()) {
+                self.warnings.push(Warning::UnreachableCode(
+                    first.span.source_id,
+                    Span::combine(&[first.span, last.span]).into(),
+                    stmts[pos].span.into(),
+                ));
+            }
+        }
+
+        walk_stmts(self, stmts);
+    }
+
+    fn visit_expr(&mut self, expr: &Expression) {
+        let always = match &expr.expr {
+            Expr::Binary { lhs, op, rhs } => constant_comparison(lhs, op.op, rhs),
+            // The parser already knows the value has the type
+            Expr::Is { expr: inner, ty } => {
+                (inner.ty == ty.ty && !matches!(ty.ty, Type::Any | Type::Union(..))).then_some(true)
+            }
+            _ => None,
+        };
+
+        if let Some(always) = always {
+            self.warnings.push(Warning::ConstantComparison(
+                expr.span.source_id,
+                expr.span.into(),
+                always,
+            ));
+        }
+
+        walk_expr(self, expr);
+    }
+}
+
+#[derive(PartialEq, PartialOrd)]
+enum Literal<'a> {
+    // Ints and floats are never equal at runtime, even when they have the same value
+    Int(i64),
+    Float(f64),
+    String(&'a str),
+    Bool(bool),
+    Null,
+}
+
+fn literal(expr: &Expression) -> Option<Literal<'_>> {
+    match &expr.expr {
+        Expr::Int(v) => Some(Literal::Int(*v)),
+        Expr::Float(v) => Some(Literal::Float(*v)),
+        Expr::String(v) => Some(Literal::String(v)),
+        Expr::Bool(v) => Some(Literal::Bool(*v)),
+        Expr::Null => Some(Literal::Null),
+        Expr::Grouping(inner) => literal(inner),
+        _ => None,
+    }
+}
+
+// Comparisons between two literals, or between a variable and itself. Ex: '1 < 2', or 'x == x'
+fn constant_comparison(lhs: &Expression, op: Op, rhs: &Expression) -> Option<bool> {
+    let ordering = match (&lhs.expr, &rhs.expr) {
+        // Instances compare with their own methods, which don't have to agree with themselves
+        (Expr::Variable(_), _) if matches!(lhs.ty, Type::Instance(_)) => return None,
+        (Expr::Variable(lhs), Expr::Variable(rhs)) if lhs == rhs => std::cmp::Ordering::Equal,
+        _ => {
+            let (lhs, rhs) = (literal(lhs)?, literal(rhs)?);
+            if std::mem::discriminant(&lhs) != std::mem::discriminant(&rhs) {
+                return None;
+            }
+            lhs.partial_cmp(&rhs)?
+        }
+    };
+
+    match op {
+        Op::Equals => Some(ordering.is_eq()),
+        Op::NotEquals => Some(ordering.is_ne()),
+        Op::LessThan => Some(ordering.is_lt()),
+        Op::LessThanEquals => Some(ordering.is_le()),
+        Op::GreaterThan => Some(ordering.is_gt()),
+        Op::GreaterThanEquals => Some(ordering.is_ge()),
+        _ => None,
+    }
+}
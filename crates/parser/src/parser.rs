@@ -1,35 +1,130 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
-    error::ParseError,
+    error::{ParseError, ParseErrors},
+    init::uninitialized_uses,
+    lint::always_returns,
     source::Source,
-    symtab::{Sym, Symbol, SymbolTable},
+    symtab::{Sym, Symbol, SymbolTable, Visibility},
     Parse,
 };
 use ast::{
-    expr::{Expr, Expression},
-    op::{Op, Operator},
-    stmt::{Binding, Class, Function, Statement, Stmt},
+    expr::{Arg, Expr, Expression},
+    op::{
+        Op, Operator, EQUALS_METHOD, HASH_METHOD, INDEX_OVERLOAD_METHOD, ITER_METHOD, NEXT_METHOD,
+        TO_STRING_METHOD,
+    },
+    stmt::{Binding, Class, Function, Param, Pattern, Statement, Stmt, Struct},
     ty::{result_type, type_compatible, Type, TypeExpression},
 };
 use lexer::{Token, TokenKind};
-use meta::{trace, Span, Spanned};
+use meta::{trace, SourceId, Span, Spanned};
+
+// Name of a member, and the type and span of the value assigned to it, if any
+type PendingMember = (Spanned<String>, Option<(Type, Span)>);
+
+// The members of the class being declared, along with their symbols
+#[derive(Default)]
+struct ClassMembers {
+    methods: Vec<Statement>,
+    method_symbols: HashMap<String, Symbol>,
+    statics: Vec<Statement>,
+    static_symbols: HashMap<String, Symbol>,
+    fields: Vec<Statement>,
+    field_symbols: HashMap<String, Symbol>,
+    getters: Vec<Statement>,
+    setters: Vec<Statement>,
+}
 
 pub struct Parser {
     source: Source,
     symtab: SymbolTable,
+    // Name of the class whose body is being parsed, if any
+    current_class: Option<String>,
+    // Members of that class that were used before it declared them. They are checked once the
+    // whole body is parsed.
+    pending_members: Option<Vec<PendingMember>>,
+    // Whether the method whose body is being parsed is static, in which case there is no 'this'
+    in_static: bool,
+    // Whether the initializer of a field is being parsed. Fields are initialized before the
+    // constructor binds the instance, so there is no 'this' there either
+    in_field_initializer: bool,
+    // Types of the values yielded by the function whose body is being parsed, if any. A function
+    // that yields is a generator
+    yields: Option<Vec<TypeExpression>>,
+    // Types of the values returned by the function whose body is being parsed, if any. 'ret;'
+    // returns null
+    returns: Option<Vec<TypeExpression>>,
+    // Name of the function whose return type is being inferred from the body that is being
+    // parsed, and the span of a call to it in the expression being returned, if any
+    inferring: Option<String>,
+    recursive_call: Option<Span>,
+    // Element type of the list a variable is declared as, while the list literal it is initialized
+    // with is being parsed. The elements are checked against it instead of against each other, so
+    // that they can have different types of a union.
+    list_element: Option<TypeExpression>,
+    // Names of the methods that have overloads, and the methods called on objects whose type
+    // isn't known. Those calls can't pick an overload, so they are checked once everything is
+    // declared.
+    overloaded_methods: HashSet<String>,
+    untyped_method_calls: Vec<Spanned<String>>,
+    // Errors of the declarations that were skipped, see decl_or_recover
+    errors: Vec<ParseError>,
 }
 
 impl Parser {
     pub fn new(source: Source, symtab: SymbolTable) -> Self {
-        Self { source, symtab }
+        Self {
+            source,
+            symtab,
+            current_class: None,
+            pending_members: None,
+            in_static: false,
+            in_field_initializer: false,
+            yields: None,
+            returns: None,
+            inferring: None,
+            recursive_call: None,
+            list_element: None,
+            overloaded_methods: HashSet::default(),
+            untyped_method_calls: vec![],
+            errors: vec![],
+        }
     }
 
     pub fn parse(mut self) -> miette::Result<Parse> {
-        Ok(Parse {
-            stmts: self.program()?,
-            symtab: self.symtab,
-        })
+        let stmts = match self.program() {
+            Ok(stmts) => stmts,
+            Err(error) => {
+                self.errors.push(error);
+                vec![]
+            }
+        };
+
+        self.errors.extend(uninitialized_uses(&stmts));
+
+        let overloaded_calls = std::mem::take(&mut self.untyped_method_calls)
+            .into_iter()
+            .filter(|name| self.overloaded_methods.contains(&name.item));
+        for name in overloaded_calls {
+            self.errors.push(ParseError::UntypedOverloadedCall(
+                self.source.id,
+                name.span.into(),
+                name.item,
+            ));
+        }
+
+        match self.errors.len() {
+            0 => Ok(Parse {
+                stmts,
+                symtab: self.symtab,
+            }),
+            1 => Err(self.errors.remove(0).into()),
+            _ => Err(ParseErrors {
+                errors: self.errors,
+            }
+            .into()),
+        }
     }
 
     fn is_callable(&self, callee: &Expression) -> Result<(), ParseError> {
@@ -42,10 +137,12 @@ impl Parser {
         match &callee.expr {
             Expr::Variable(name) => {
                 if let Some(entry) = self.symtab.lookup(name) {
+                    // Enums have no constructor
                     if matches!(
                         entry.ty,
                         Type::Any | Type::Function { .. } | Type::Class(..)
-                    ) {
+                    ) && !matches!(entry.sym, Sym::Enum { .. })
+                    {
                         return Ok(());
                     }
                 }
@@ -53,18 +150,603 @@ impl Parser {
                 err
             }
 
-            // TODO nested get exprs
-            Expr::Get { .. } => Ok(()),
+            // Properties are callable when their value is, so getters usually aren't
+            Expr::Get { .. }
+                if matches!(
+                    callee.ty,
+                    Type::Any | Type::Function { .. } | Type::Class(..)
+                ) =>
+            {
+                Ok(())
+            }
             _ => err,
         }
     }
 
+    // Methods like 'to_string' are called implicitly, so their signatures are fixed
+    fn check_protocol_method(&self, func: &Function) -> Result<(), ParseError> {
+        // Comparisons are always bool, even when an operand overloads them
+        let comparisons = [
+            Op::Equals,
+            Op::LessThan,
+            Op::LessThanEquals,
+            Op::GreaterThan,
+            Op::GreaterThanEquals,
+        ];
+
+        let (params, expected, signature) = match func.name.item.as_str() {
+            TO_STRING_METHOD => (0, Type::String, "to_string() -> string".to_string()),
+            EQUALS_METHOD => (1, Type::Bool, "equals(other) -> bool".to_string()),
+            HASH_METHOD => (0, Type::Int, "hash() -> int".to_string()),
+            name if comparisons
+                .iter()
+                .any(|op| op.overload_method() == Some(name)) =>
+            {
+                (1, Type::Bool, format!("{name}(other) -> bool"))
+            }
+            _ => return Ok(()),
+        };
+
+        let returns_ok = match &func.ty.ty {
+            Type::Function { returns, .. } => type_compatible(&expected, &returns.ty),
+            _ => true,
+        };
+
+        if func.params.len() != params || func.params.iter().any(|p| p.rest) || !returns_ok {
+            return Err(ParseError::InvalidProtocolMethod(
+                self.source.id,
+                func.name.span.into(),
+                func.name.item.clone(),
+                signature,
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn class_method(&self, class_name: &str, method: &str) -> Option<&Symbol> {
+        match self.symtab.lookup(class_name) {
+            Some(Symbol {
+                sym: Sym::Class { methods, .. },
+                ..
+            }) => lookup_overloaded(method, |name| methods.get(name)),
+            _ => None,
+        }
+    }
+
+    // Declaring a function again in the same scope with different param types overloads it.
+    // Returns the name the function is declared under.
+    fn declare_function(&mut self, symbol: Symbol) -> Result<String, ParseError> {
+        match self.symtab.lookup_local_mut(&symbol.name.item) {
+            Some(
+                primary @ Symbol {
+                    sym: Sym::Function { .. },
+                    ..
+                },
+            ) => add_overload(self.source.id, primary, symbol),
+            _ => {
+                let name = symbol.name.item.clone();
+                self.symtab.insert(symbol);
+                Ok(name)
+            }
+        }
+    }
+
+    fn function_symbol_mut(&mut self, name: &str, declared_as: &str) -> &mut Symbol {
+        let symbol = self
+            .symtab
+            .lookup_mut(name)
+            .expect("ICE: couldn't find func symbol to update ret type");
+        if name == declared_as {
+            return symbol;
+        }
+
+        symbol
+            .overload_mut(declared_as)
+            .expect("ICE: couldn't find overload to update ret type")
+    }
+
+    // Calls to overloaded functions and methods are resolved to the overload whose params fit the
+    // arguments, preferring the one with the most arguments of the exact same type
+    fn resolve_overload(
+        &self,
+        mut callee: Expression,
+        args: &[Arg],
+    ) -> Result<Expression, ParseError> {
+        let primary = match &callee.expr {
+            Expr::Variable(name) => self.symtab.lookup(name),
+            Expr::Get { object, name } => match &object.ty {
+                Type::Instance(class_name) => self.class_method(class_name, &name.item),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        let candidates = match primary {
+            Some(
+                primary @ Symbol {
+                    sym: Sym::Function { overloads, .. },
+                    ..
+                },
+            ) if !overloads.is_empty() => std::iter::once(primary).chain(overloads),
+            _ => return Ok(callee),
+        };
+
+        let mut matches = vec![];
+        for candidate in candidates {
+            if let Type::Function {
+                params, variadic, ..
+            } = &candidate.ty
+            {
+                if self
+                    .check_args(&callee, Some(candidate), params, *variadic, args)
+                    .is_ok()
+                {
+                    matches.push((exact_matches(candidate, params, args), candidate));
+                }
+            }
+        }
+
+        let best = matches.iter().map(|(exact, _)| *exact).max();
+        let matches = matches
+            .into_iter()
+            .filter(|(exact, _)| Some(*exact) == best)
+            .map(|(_, candidate)| candidate)
+            .collect::<Vec<_>>();
+
+        let base_name = primary.map_or_else(String::new, |primary| primary.name.item.clone());
+        let chosen = match matches.as_slice() {
+            [chosen] => *chosen,
+            [] => {
+                return Err(ParseError::NoMatchingOverload(
+                    self.source.id,
+                    callee.span.into(),
+                    base_name,
+                    args.iter()
+                        .map(|arg| format!("{}", arg.value.ty))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ))
+            }
+            [first, second, ..] => {
+                return Err(ParseError::AmbiguousCall(
+                    self.source.id,
+                    callee.span.into(),
+                    base_name,
+                    first.name.span.into(),
+                    second.name.span.into(),
+                ))
+            }
+        };
+
+        callee.ty = chosen.ty.clone();
+        match &mut callee.expr {
+            Expr::Variable(name) => *name = chosen.name.item.clone(),
+            Expr::Get { name, .. } => name.item = chosen.name.item.clone(),
+            _ => unreachable!("ICE: only variables and methods can be overloaded"),
+        }
+
+        Ok(callee)
+    }
+
+    // Instances can only use the fields and methods declared by their class. While a class is
+    // being declared its members aren't all known yet, so inside of its body the unknown ones are
+    // checked after the rest of the body is parsed. Properties that are about to be assigned don't
+    // need a getter, see check_field_assignment.
+    fn property_type(
+        &mut self,
+        object: &Type,
+        name: &Spanned<String>,
+        assigned: bool,
+    ) -> Result<Type, ParseError> {
+        // Generators only have 'next', which returns null once they are finished
+        if let Type::Generator(ty) = object {
+            return match name.item.as_str() {
+                "next" => Ok(Type::Function {
+                    params: vec![],
+                    returns: Box::new(TypeExpression {
+                        ty: unify_types(vec![
+                            *ty.clone(),
+                            TypeExpression {
+                                ty: Type::Null,
+                                span: ty.span,
+                            },
+                        ]),
+                        span: ty.span,
+                    }),
+                    variadic: false,
+                }),
+                _ => Err(ParseError::UnknownProperty(
+                    self.source.id,
+                    name.span.into(),
+                    object.clone(),
+                    name.item.clone(),
+                )),
+            };
+        }
+
+        if let Type::Instance(class_name) = object {
+            if let Some(Symbol {
+                sym: Sym::Class {
+                    methods, fields, ..
+                },
+                ..
+            }) = self.symtab.lookup(class_name)
+            {
+                if let Some(field) = fields.get(&name.item) {
+                    self.check_visible(class_name, field, name)?;
+                    if let (Sym::Property { get: false, .. }, false) = (&field.sym, assigned) {
+                        return Err(ParseError::WriteOnlyProperty(
+                            self.source.id,
+                            name.span.into(),
+                            name.item.clone(),
+                        ));
+                    }
+
+                    return Ok(field.ty.clone());
+                }
+
+                if let Some(method) = methods.get(&name.item) {
+                    self.check_visible(class_name, method, name)?;
+                    return Ok(method.ty.clone());
+                }
+
+                match &mut self.pending_members {
+                    Some(pending) if self.current_class.as_ref() == Some(class_name) => {
+                        pending.push((name.clone(), None));
+                    }
+                    _ => {
+                        return Err(ParseError::UnknownProperty(
+                            self.source.id,
+                            name.span.into(),
+                            object.clone(),
+                            name.item.clone(),
+                        ))
+                    }
+                }
+            }
+        }
+
+        if let Type::Class(class_name) = object {
+            if let Some(Symbol {
+                sym: Sym::Class { statics, .. } | Sym::Enum { statics },
+                ..
+            }) = self.symtab.lookup(class_name)
+            {
+                if let Some(symbol) = statics.get(&name.item) {
+                    self.check_visible(class_name, symbol, name)?;
+                    return Ok(symbol.ty.clone());
+                }
+
+                if self.current_class.as_ref() != Some(class_name) {
+                    return Err(ParseError::UnknownStaticProperty(
+                        self.source.id,
+                        name.span.into(),
+                        object.clone(),
+                        name.item.clone(),
+                    ));
+                }
+            }
+        }
+
+        Ok(Type::Any)
+    }
+
+    // Matches arguments to params, positional ones first and then by name. Named arguments and
+    // default values can only be used when the declaration of the callee is known, since function
+    // values only have the types of their params. Once a list is spread the
+    // positions of the arguments after it aren't known until runtime, so they have to fit every
+    // param they could end up in
+    fn check_args(
+        &self,
+        callee: &Expression,
+        decl: Option<&Symbol>,
+        params: &[TypeExpression],
+        variadic: bool,
+        args: &[Arg],
+    ) -> Result<(), ParseError> {
+        let fixed = params.len() - usize::from(variadic);
+        let (required, names): (usize, &[String]) = match decl {
+            Some(Symbol {
+                sym: Sym::Function {
+                    required, params, ..
+                },
+                ..
+            }) => (*required, params),
+            _ => (fixed, &[]),
+        };
+
+        let mut spread: Option<usize> = None;
+        let mut spread_positionals = 0;
+        let mut given: Vec<Option<&Arg>> = vec![None; params.len()];
+        for (pos, arg) in args.iter().enumerate() {
+            let idx = match &arg.name {
+                Some(name) => match names.iter().position(|param| param == &name.item) {
+                    Some(idx) => idx,
+                    None => {
+                        return Err(ParseError::UnknownParameter(
+                            self.source.id,
+                            name.span.into(),
+                            name.item.clone(),
+                        ))
+                    }
+                },
+                None if args[..pos].iter().any(|arg| arg.name.is_some()) => {
+                    return Err(ParseError::PositionalAfterNamed(
+                        self.source.id,
+                        arg.value.span.into(),
+                    ))
+                }
+                None if spread.is_some() || matches!(arg.value.expr, Expr::Spread(..)) => {
+                    let start = *spread.get_or_insert(pos);
+                    let ty = match &arg.value.expr {
+                        Expr::Spread(..) => element_type(&arg.value.ty),
+                        _ => {
+                            spread_positionals += 1;
+                            arg.value.ty.clone()
+                        }
+                    };
+
+                    for (idx, param) in params.iter().enumerate().skip(start) {
+                        let param_ty = if idx < fixed {
+                            param.ty.clone()
+                        } else {
+                            element_type(&param.ty)
+                        };
+                        if !type_compatible(&param_ty, &ty) {
+                            return Err(ParseError::IncompatibleTypes(
+                                self.source.id,
+                                param.span.into(),
+                                param_ty,
+                                arg.value.span.into(),
+                                ty,
+                            ));
+                        }
+                    }
+
+                    continue;
+                }
+                None if pos < fixed => pos,
+                None if variadic => {
+                    // Extra arguments are collected into the rest param
+                    let rest = &params[fixed];
+                    let ty = element_type(&rest.ty);
+                    if !type_compatible(&ty, &arg.value.ty) {
+                        return Err(ParseError::IncompatibleTypes(
+                            self.source.id,
+                            rest.span.into(),
+                            ty,
+                            arg.value.span.into(),
+                            arg.value.ty.clone(),
+                        ));
+                    }
+
+                    given[fixed] = Some(arg);
+                    continue;
+                }
+                None => {
+                    return Err(ParseError::ArityMismatch(
+                        self.source.id,
+                        callee.span.into(),
+                        params.len(),
+                        args.len(),
+                    ))
+                }
+            };
+
+            if given[idx].is_some() {
+                return Err(ParseError::DuplicateArgument(
+                    self.source.id,
+                    arg.value.span.into(),
+                    names[idx].clone(),
+                ));
+            }
+
+            let param = &params[idx];
+            if !type_compatible(&param.ty, &arg.value.ty) {
+                return Err(ParseError::IncompatibleTypes(
+                    self.source.id,
+                    param.span.into(),
+                    param.ty.clone(),
+                    arg.value.span.into(),
+                    arg.value.ty.clone(),
+                ));
+            }
+
+            given[idx] = Some(arg);
+        }
+
+        // Spread lists can be empty, but the other positional arguments still need a param
+        if let Some(start) = spread {
+            if !variadic && start + spread_positionals > fixed {
+                return Err(ParseError::ArityMismatch(
+                    self.source.id,
+                    callee.span.into(),
+                    params.len(),
+                    args.len(),
+                ));
+            }
+
+            return Ok(());
+        }
+
+        if let Some(idx) = given.iter().take(required).position(Option::is_none) {
+            return Err(match names.get(idx) {
+                Some(name) => {
+                    ParseError::MissingArgument(self.source.id, callee.span.into(), name.clone())
+                }
+                None => ParseError::ArityMismatch(
+                    self.source.id,
+                    callee.span.into(),
+                    params.len(),
+                    args.len(),
+                ),
+            });
+        }
+
+        Ok(())
+    }
+
+    // Finds the declaration of a function or method being called, if it is known statically
+    fn callee_decl(&self, callee: &Expression) -> Option<&Symbol> {
+        let decl = match &callee.expr {
+            Expr::Variable(name) => lookup_overloaded(name, |name| self.symtab.lookup(name)),
+            Expr::Get { object, name } => match &object.ty {
+                Type::Instance(class_name) => self.class_method(class_name, &name.item),
+                Type::Class(class_name) => match self.symtab.lookup(class_name) {
+                    Some(Symbol {
+                        sym: Sym::Class { statics, .. } | Sym::Enum { statics },
+                        ..
+                    }) => statics.get(&name.item),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        };
+
+        decl.filter(|decl| matches!(decl.sym, Sym::Function { .. }))
+    }
+
+    fn check_visible(
+        &self,
+        class_name: &str,
+        member: &Symbol,
+        usage: &Spanned<String>,
+    ) -> Result<(), ParseError> {
+        if member.vis == Visibility::Private && self.current_class.as_deref() != Some(class_name) {
+            Err(ParseError::PrivateMember(
+                self.source.id,
+                usage.span.into(),
+                class_name.to_string(),
+                usage.item.clone(),
+                member.name.span.into(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_field_assignment(
+        &mut self,
+        object: &Type,
+        name: &Spanned<String>,
+        rhs: (&Type, Span),
+    ) -> Result<(), ParseError> {
+        let (rhs_ty, rhs_span) = rhs;
+        if let Type::Instance(class_name) = object {
+            if let Some(Symbol {
+                sym: Sym::Class { fields, .. },
+                ..
+            }) = self.symtab.lookup(class_name)
+            {
+                if let Some(field) = fields.get(&name.item) {
+                    self.check_visible(class_name, field, name)?;
+                    if let Sym::Property { set: false, .. } = field.sym {
+                        return Err(ParseError::ReadOnlyProperty(
+                            self.source.id,
+                            name.span.into(),
+                            name.item.clone(),
+                        ));
+                    }
+
+                    if !type_compatible(&field.ty, rhs_ty) {
+                        return Err(ParseError::IncompatibleTypes(
+                            self.source.id,
+                            name.span.into(),
+                            field.ty.clone(),
+                            rhs_span.into(),
+                            rhs_ty.clone(),
+                        ));
+                    }
+                } else {
+                    match &mut self.pending_members {
+                        Some(pending) if self.current_class.as_ref() == Some(class_name) => {
+                            // The field was deferred as a read when its name was parsed
+                            pending.retain(|(member, _)| member.span != name.span);
+                            pending.push((name.clone(), Some((rhs_ty.clone(), rhs_span))));
+                        }
+                        _ => {
+                            return Err(ParseError::UnknownField(
+                                self.source.id,
+                                name.span.into(),
+                                object.clone(),
+                                name.item.clone(),
+                            ))
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Type checks a call to an operator overload method, returning the type it produces
+    fn overload_type(&self, method: &Symbol, rhs: &Expression) -> Result<Type, ParseError> {
+        if let Type::Function {
+            params, returns, ..
+        } = &method.ty
+        {
+            if let Some(param) = params.first() {
+                if !type_compatible(&param.ty, &rhs.ty) {
+                    return Err(ParseError::IncompatibleTypes(
+                        self.source.id,
+                        param.span.into(),
+                        param.ty.clone(),
+                        rhs.span.into(),
+                        rhs.ty.clone(),
+                    ));
+                }
+            }
+
+            Ok(returns.ty.clone())
+        } else {
+            Ok(Type::Any)
+        }
+    }
+
     fn result_type(
         &self,
         lhs: &Expression,
         op: &Operator,
         rhs: &Expression,
     ) -> Result<Type, ParseError> {
+        // Instances can overload operators by defining methods like 'op_add'
+        if let Type::Instance(class_name) = &lhs.ty {
+            if let Some(method) = op
+                .op
+                .overload_method()
+                .and_then(|method| self.class_method(class_name, method))
+            {
+                let ty = self.overload_type(method, rhs)?;
+
+                return Ok(match op.op {
+                    Op::Equals | Op::NotEquals => Type::Bool,
+                    _ => ty,
+                });
+            }
+
+            // Without 'op_eq', '==' and '!=' fall back to an 'equals' method
+            if matches!(op.op, Op::Equals | Op::NotEquals) {
+                if let Some(method) = self.class_method(class_name, EQUALS_METHOD) {
+                    self.overload_type(method, rhs)?;
+                    return Ok(Type::Bool);
+                }
+            }
+        }
+
+        // Strings can be concatenated with instances that define 'to_string'
+        if op.op == Op::Add {
+            if let (Type::String, Type::Instance(class_name))
+            | (Type::Instance(class_name), Type::String) = (&lhs.ty, &rhs.ty)
+            {
+                if self.class_method(class_name, TO_STRING_METHOD).is_some() {
+                    return Ok(Type::String);
+                }
+            }
+        }
+
         if let Some(ty) = result_type(lhs, op, rhs) {
             Ok(ty)
         } else {
@@ -72,9 +754,9 @@ impl Parser {
                 self.source.id,
                 op.span.into(),
                 lhs.span.into(),
-                lhs.ty.clone(),
+                Box::new(lhs.ty.clone()),
                 rhs.span.into(),
-                rhs.ty.clone(),
+                Box::new(rhs.ty.clone()),
             ))
         }
     }
@@ -94,7 +776,7 @@ impl Parser {
     }
 
     fn at_set(&mut self, set: &[TokenKind]) -> bool {
-        self.source.peek_kind().map_or(false, |k| set.contains(&k))
+        self.source.peek_kind().is_some_and(|k| set.contains(&k))
     }
 
     fn expect(&mut self, kind: TokenKind) -> Result<&Token, ParseError> {
@@ -119,88 +801,402 @@ impl Parser {
         trace!("parse_program");
         let mut stmts: Vec<Statement> = Vec::new();
         while !self.source.at_end() {
-            stmts.push(self.decl()?);
+            stmts.extend(self.decl_or_recover()?);
         }
 
         Ok(stmts)
     }
 
-    fn decl(&mut self) -> Result<Statement, ParseError> {
-        trace!("parse_decl");
-        if self.at(TokenKind::Class) {
-            self.class_decl()
-        } else if self.at(TokenKind::Func) {
-            self.func_decl(false)
-        } else if self.at(TokenKind::Let) {
-            self.var_decl()
-        } else if self.at(TokenKind::Enum) {
-            self.enum_decl()
-        } else {
-            self.stmt()
+    // Parses a declaration, or records its error and skips ahead to where the next one might start
+    // so the rest of the source still gets checked. Returns an error once there is nothing left.
+    fn decl_or_recover(&mut self) -> Result<Option<Statement>, ParseError> {
+        let checkpoint = self.source.checkpoint();
+        let depth = self.symtab.depth();
+        let current_class = self.current_class.clone();
+        let pending_members = self.pending_members.clone();
+        let in_static = self.in_static;
+        let yields = self.yields.clone();
+        let returns = self.returns.clone();
+        let inferring = self.inferring.clone();
+        let type_decl = self.at_type_decl();
+
+        match self.decl() {
+            Ok(stmt) => Ok(Some(stmt)),
+            Err(error) if self.source.at_end() => Err(error),
+            Err(error) => {
+                self.errors.push(error);
+
+                // Undo whatever the declaration was in the middle of
+                self.symtab.truncate(depth);
+                self.current_class = current_class;
+                self.pending_members = pending_members;
+                self.in_static = in_static;
+                self.yields = yields;
+                self.returns = returns;
+                self.inferring = inferring;
+
+                // The members of a type can't be parsed as declarations of their own, so the whole
+                // body is skipped
+                if type_decl {
+                    self.source.restore(checkpoint);
+                    self.skip_body()?;
+                    return Ok(None);
+                }
+
+                // Always skip at least one token, so the same error isn't found again
+                if self.source.checkpoint() == checkpoint {
+                    self.bump()?;
+                }
+                self.synchronize()?;
+
+                Ok(None)
+            }
         }
     }
 
-    // Enums are just syntactical sugar for static classes
-    fn enum_decl(&mut self) -> Result<Statement, ParseError> {
-        trace!("parse_enum_decl");
-        let enum_token_span = self.expect(TokenKind::Enum)?.span;
-        let name_token = self.expect(TokenKind::Ident)?;
-
-        let spanned_name: Spanned<String> = name_token.into();
+    // Skips to the end of the current statement, the start of the next declaration, or the '}'
+    // that closes the current block. There is no block to close at the top level, so stray '}'s
+    // are skipped there.
+    fn synchronize(&mut self) -> Result<(), ParseError> {
+        let mut depth = 0;
+        while let Some(kind) = self.source.peek_kind() {
+            match kind {
+                TokenKind::LeftBrace => depth += 1,
+                TokenKind::RightBrace if depth == 0 && !self.symtab.at_global_scope() => {
+                    return Ok(())
+                }
+                TokenKind::RightBrace if depth > 0 => depth -= 1,
+                TokenKind::Semicolon if depth == 0 => {
+                    self.bump()?;
+                    return Ok(());
+                }
+                TokenKind::Class
+                | TokenKind::Func
+                | TokenKind::Let
+                | TokenKind::Enum
+                | TokenKind::Struct
+                | TokenKind::Pub
+                | TokenKind::If
+                | TokenKind::Until
+                | TokenKind::For
+                | TokenKind::Ret
+                    if depth == 0 =>
+                {
+                    return Ok(())
+                }
+                _ => {}
+            }
 
-        let name = name_token.text.to_string();
-        let name_span = name_token.span;
+            self.bump()?;
+        }
 
-        self.expect(TokenKind::LeftBrace)?;
+        Ok(())
+    }
 
-        let mut statics = Vec::new();
-        let mut static_symbols = HashMap::default();
+    fn at_type_decl(&mut self) -> bool {
+        self.at_set(&[TokenKind::Class, TokenKind::Enum, TokenKind::Struct])
+    }
 
-        if !self.at(TokenKind::RightBrace) {
-            loop {
-                let enum_kind = self.expect(TokenKind::Ident)?;
-                let stmt = Statement {
-                    stmt: Stmt::Variable {
-                        name: Binding {
-                            name: enum_kind.into(),
-                            ty: Type::Int,
-                        },
-                        expr: Some(Expression {
-                            expr: Expr::Int(statics.len() as i64),
-                            span: Span::garbage(),
-                            ty: Type::Int,
-                        }),
-                    },
-                    span: enum_kind.span,
-                };
+    // Looks ahead for a 'yield' in the body that is about to be parsed, leaving out the bodies of
+    // the functions and types declared inside of it
+    fn body_yields(&mut self) -> bool {
+        let checkpoint = self.source.checkpoint();
+        let mut depth = 0;
+        let mut nested: Option<usize> = None;
+        let mut declaring = false;
+        let mut yields = false;
+        while let Some(token) = self.source.next_token() {
+            match token.kind {
+                TokenKind::LeftBrace => {
+                    depth += 1;
+                    if declaring && nested.is_none() {
+                        nested = Some(depth);
+                    }
+                    declaring = false;
+                }
+                TokenKind::RightBrace => {
+                    if nested == Some(depth) {
+                        nested = None;
+                    }
+                    depth -= 1;
+                }
+                TokenKind::Func | TokenKind::Class | TokenKind::Enum | TokenKind::Struct => {
+                    declaring = true
+                }
+                TokenKind::Yield if nested.is_none() => yields = true,
+                _ => {}
+            }
+
+            if yields || depth == 0 {
+                break;
+            }
+        }
+
+        self.source.restore(checkpoint);
+        yields
+    }
+
+    // Skips past the '}' that closes the first block, or the end of a statement before it, unless
+    // the current block closes first
+    fn skip_body(&mut self) -> Result<(), ParseError> {
+        let mut depth = 0;
+        while let Some(kind) = self.source.peek_kind() {
+            match kind {
+                TokenKind::RightBrace if depth == 0 => return Ok(()),
+                TokenKind::RightBrace if depth == 1 => {
+                    self.bump()?;
+                    return Ok(());
+                }
+                TokenKind::Semicolon if depth == 0 => {
+                    self.bump()?;
+                    return Ok(());
+                }
+                TokenKind::RightBrace => depth -= 1,
+                TokenKind::LeftBrace => depth += 1,
+                _ => {}
+            }
+
+            self.bump()?;
+        }
+
+        Ok(())
+    }
+
+    fn decl(&mut self) -> Result<Statement, ParseError> {
+        trace!("parse_decl");
+        if self.at(TokenKind::Class) {
+            self.class_decl()
+        } else if self.at(TokenKind::Func) {
+            self.func_decl(false)
+        } else if self.at(TokenKind::Let) {
+            self.var_decl()
+        } else if self.at(TokenKind::Enum) {
+            self.enum_decl()
+        } else if self.at(TokenKind::Struct) {
+            self.struct_decl()
+        } else if self.at_type_alias() {
+            self.type_alias_decl()
+        } else if self.at_set(&[TokenKind::Pub, TokenKind::Priv]) {
+            // Only class members have a visibility, there are no modules to export symbols to
+            Err(ParseError::InvalidVisibilityModifier(
+                self.source.id,
+                self.bump()?.span.into(),
+            ))
+        } else {
+            self.stmt()
+        }
+    }
+
+    // 'type' is not a keyword, so that it can still be used as a name, like the builtin type()
+    fn at_type_alias(&mut self) -> bool {
+        self.source.peek_text() == Some("type")
+            && self.source.peek_nth_kind(1) == Some(TokenKind::Ident)
+    }
+
+    fn type_alias_decl(&mut self) -> Result<Statement, ParseError> {
+        trace!("parse_type_alias_decl");
+        let type_token_span = self.bump()?.span;
+        let name: Spanned<String> = self.expect(TokenKind::Ident)?.into();
+        self.expect(TokenKind::Equal)?;
+        let ty = self.ty()?;
 
+        if let Some(previous) = self.symtab.lookup_local(&name.item) {
+            return Err(ParseError::CannotRedeclareSymbol(
+                self.source.id,
+                name.item.clone(),
+                name.span.into(),
+                previous.name.span.into(),
+            ));
+        }
+
+        // Aliases are replaced by their type when they are used, which would never end if the
+        // type used the alias
+        if self.symtab.lookup(&name.item).is_none() {
+            if let Some(span) = instance_span(&ty, &name.item) {
+                return Err(ParseError::RecursiveTypeAlias(
+                    self.source.id,
+                    span.into(),
+                    name.item,
+                ));
+            }
+        }
+
+        let semi_span = self.expect(TokenKind::Semicolon)?.span;
+        self.symtab.insert(Symbol {
+            name: name.clone(),
+            sym: Sym::TypeAlias,
+            ty: ty.ty.clone(),
+            vis: Visibility::Private,
+        });
+
+        Ok(Statement {
+            span: Span::combine(&[type_token_span, semi_span]),
+            stmt: Stmt::TypeAlias { name, ty },
+        })
+    }
+
+    // Enums are just syntactical sugar for static classes. Members are numbered from 0 unless
+    // they are given a value, and the enum gets 'name(member)' and 'values()' for free. Members
+    // are plain values, so methods declared after them are called on the enum itself.
+    fn enum_decl(&mut self) -> Result<Statement, ParseError> {
+        trace!("parse_enum_decl");
+        let enum_token_span = self.expect(TokenKind::Enum)?.span;
+        let name_token = self.expect(TokenKind::Ident)?;
+
+        let spanned_name: Spanned<String> = name_token.into();
+
+        let name = name_token.text.to_string();
+        let name_span = name_token.span;
+        let ty = Type::Enum(name.clone());
+
+        self.expect(TokenKind::LeftBrace)?;
+
+        let mut statics = Vec::new();
+        let mut static_symbols: HashMap<String, Symbol> = HashMap::default();
+        let mut members: Vec<(Spanned<String>, i64)> = Vec::new();
+
+        while self.at(TokenKind::Ident)
+            && self.source.peek_nth_kind(1) != Some(TokenKind::LeftParen)
+        {
+            let enum_kind: Spanned<String> = self.expect(TokenKind::Ident)?.into();
+
+            // Members are reached the same way as the generated functions, so they can't share
+            // their names
+            if ENUM_API.contains(&enum_kind.item.as_str()) {
+                return Err(ParseError::ReservedEnumMember(
+                    self.source.id,
+                    enum_kind.span.into(),
+                    enum_kind.item,
+                ));
+            }
+
+            let value = if self.at(TokenKind::Equal) {
+                self.bump()?;
+                self.enum_value()?
+            } else {
+                match members.last() {
+                    Some((_, value)) => value.checked_add(1).ok_or_else(|| {
+                        ParseError::EnumValueOutOfRange(self.source.id, enum_kind.span.into())
+                    })?,
+                    None => 0,
+                }
+            };
+
+            if let Some(previous) = static_symbols.get(&enum_kind.item) {
+                return Err(ParseError::CannotRedeclareSymbol(
+                    self.source.id,
+                    enum_kind.item.clone(),
+                    enum_kind.span.into(),
+                    previous.name.span.into(),
+                ));
+            }
+
+            if let Some((previous, _)) = members.iter().find(|(_, v)| *v == value) {
+                return Err(ParseError::DuplicateEnumValue(
+                    self.source.id,
+                    enum_kind.span.into(),
+                    value,
+                    previous.span.into(),
+                ));
+            }
+
+            statics.push(Statement {
+                stmt: Stmt::Variable {
+                    name: Binding {
+                        name: enum_kind.clone(),
+                        ty: ty.clone(),
+                    },
+                    expr: Some(Expression {
+                        expr: Expr::Int(value),
+                        span: enum_kind.span,
+                        ty: ty.clone(),
+                    }),
+                },
+                span: enum_kind.span,
+            });
+
+            static_symbols.insert(
+                enum_kind.item.clone(),
+                Symbol {
+                    sym: Sym::Variable,
+                    name: enum_kind.clone(),
+                    ty: ty.clone(),
+                    vis: Visibility::Public,
+                },
+            );
+
+            members.push((enum_kind, value));
+
+            if self.at(TokenKind::Comma) {
+                self.bump()?;
+            } else {
+                break;
+            }
+        }
+
+        // Methods declared by the enum take precedence over the generated ones
+        for func in enum_api(&spanned_name, &members) {
+            if !static_symbols.contains_key(&func.name.item) {
                 static_symbols.insert(
-                    enum_kind.text.clone(),
+                    func.name.item.clone(),
                     Symbol {
-                        sym: Sym::Variable,
-                        name: enum_kind.into(),
-                        ty: Type::Int,
+                        name: func.name.clone(),
+                        sym: function_sym(&func.params),
+                        ty: func.ty.ty.clone(),
+                        vis: Visibility::Public,
                     },
                 );
+                statics.push(Statement {
+                    span: func.name.span,
+                    stmt: Stmt::Function(func),
+                });
+            }
+        }
 
-                statics.push(stmt);
+        let enclosing_class = self.current_class.replace(name.clone());
+        let enclosing_static = std::mem::replace(&mut self.in_static, true);
+        self.declare_enum(&spanned_name, &static_symbols);
 
-                if self.at(TokenKind::Comma) {
-                    self.bump()?;
-                } else {
-                    break;
+        while !self.source.at_end() && !self.at(TokenKind::RightBrace) {
+            let vis = self.member_visibility()?;
+            let stmt = self.func_decl(true)?;
+            if let Stmt::Function(func) = &stmt.stmt {
+                if members
+                    .iter()
+                    .any(|(member, _)| member.item == func.name.item)
+                {
+                    return Err(ParseError::CannotRedeclareSymbol(
+                        self.source.id,
+                        func.name.item.clone(),
+                        func.name.span.into(),
+                        static_symbols[&func.name.item].name.span.into(),
+                    ));
                 }
+
+                // Replaces a generated function with the same name
+                statics.retain(|generated| {
+                    !matches!(&generated.stmt, Stmt::Function(f) if f.name.item == func.name.item)
+                });
+
+                static_symbols.insert(
+                    func.name.item.clone(),
+                    Symbol {
+                        name: func.name.clone(),
+                        sym: function_sym(&func.params),
+                        ty: func.ty.ty.clone(),
+                        vis,
+                    },
+                );
+            } else {
+                panic!("ICE: func_decl returned a stmt that wasnt a function");
             }
+
+            statics.push(stmt);
+            self.declare_enum(&spanned_name, &static_symbols);
         }
 
-        self.symtab.insert(Symbol {
-            name: spanned_name,
-            ty: Type::Class(name.clone()),
-            sym: Sym::Class {
-                methods: HashMap::default(),
-                statics: static_symbols,
-            },
-        });
+        self.current_class = enclosing_class;
+        self.in_static = enclosing_static;
 
         let right_brace = self.expect(TokenKind::RightBrace)?;
 
@@ -213,10 +1209,127 @@ impl Parser {
                 },
                 methods: vec![],
                 statics,
+                fields: vec![],
+                getters: vec![],
+                setters: vec![],
             }),
         })
     }
 
+    // Ex: 200, or -1
+    fn enum_value(&mut self) -> Result<i64, ParseError> {
+        let sign = if self.at(TokenKind::Minus) {
+            Some(self.bump()?.span)
+        } else {
+            None
+        };
+
+        // The sign is parsed along with the digits, since i64::MIN has no positive counterpart
+        let token = self.expect(TokenKind::Int)?;
+        let span = Span::combine(&[sign.unwrap_or(token.span), token.span]);
+        let text = match sign {
+            Some(..) => format!("-{}", token.text),
+            None => token.text.to_string(),
+        };
+
+        text.parse::<i64>()
+            .map_err(|_| ParseError::EnumValueOutOfRange(self.source.id, span.into()))
+    }
+
+    fn declare_enum(&mut self, name: &Spanned<String>, statics: &HashMap<String, Symbol>) {
+        self.symtab.insert(Symbol {
+            name: name.clone(),
+            ty: Type::Class(name.item.clone()),
+            vis: Visibility::Private,
+            sym: Sym::Enum {
+                statics: statics.clone(),
+            },
+        });
+    }
+
+    // Ex: struct Point { x: int; y: int = 0; }
+    fn struct_decl(&mut self) -> Result<Statement, ParseError> {
+        trace!("parse_struct_decl");
+        let struct_token_span = self.expect(TokenKind::Struct)?.span;
+        let name: Spanned<String> = self.expect(TokenKind::Ident)?.into();
+        self.expect(TokenKind::LeftBrace)?;
+
+        // Fields are parsed like params, so default values can use earlier fields
+        self.symtab.level_up();
+
+        let mut fields: Vec<Param> = Vec::new();
+        let mut field_symbols: HashMap<String, Symbol> = HashMap::default();
+        while !self.source.at_end() && !self.at(TokenKind::RightBrace) {
+            let field = self.param(fields.last())?;
+            if field.rest {
+                return Err(ParseError::InvalidRestParameter(
+                    self.source.id,
+                    field.name.span.into(),
+                ));
+            }
+
+            if let Some(previous) = field_symbols.get(&field.name.item) {
+                return Err(ParseError::CannotRedeclareSymbol(
+                    self.source.id,
+                    field.name.item.clone(),
+                    field.name.span.into(),
+                    previous.name.span.into(),
+                ));
+            }
+
+            let symbol = Symbol {
+                name: field.name.clone(),
+                sym: Sym::Variable,
+                ty: field.ty.clone(),
+                vis: Visibility::Public,
+            };
+            self.symtab.insert(symbol.clone());
+            field_symbols.insert(field.name.item.clone(), symbol);
+
+            self.expect(TokenKind::Semicolon)?;
+            fields.push(field);
+        }
+
+        self.symtab.level_down();
+
+        let right_brace_span = self.expect(TokenKind::RightBrace)?.span;
+
+        // Structs are declared like classes whose constructor takes every field, so that calls
+        // and field accesses are checked the same way
+        let constructor = Symbol {
+            name: Spanned {
+                item: String::from("constructor"),
+                span: name.span,
+            },
+            sym: function_sym(&fields),
+            ty: Type::Function {
+                params: fields.iter().cloned().map(|field| field.into()).collect(),
+                returns: Box::new(TypeExpression {
+                    ty: Type::Instance(name.item.clone()),
+                    span: name.span,
+                }),
+                variadic: false,
+            },
+            vis: Visibility::Public,
+        };
+
+        self.symtab.insert(Symbol {
+            name: name.clone(),
+            ty: Type::Class(name.item.clone()),
+            vis: Visibility::Private,
+            sym: Sym::Class {
+                methods: HashMap::from([(String::from("constructor"), constructor)]),
+                statics: HashMap::default(),
+                fields: field_symbols,
+            },
+        });
+
+        Ok(Statement {
+            span: Span::combine(&[struct_token_span, right_brace_span]),
+            stmt: Stmt::Struct(Struct { name, fields }),
+        })
+    }
+
     fn class_decl(&mut self) -> Result<Statement, ParseError> {
         trace!("parse_class_decl");
         let class_token_span = self.expect(TokenKind::Class)?.span;
@@ -228,96 +1341,456 @@ impl Parser {
 
         self.expect(TokenKind::LeftBrace)?;
 
-        let mut methods = Vec::new();
-        let mut method_symbols = HashMap::default();
+        let mut members = ClassMembers::default();
 
-        let mut statics = Vec::new();
-        let mut static_symbols = HashMap::default();
+        let enclosing_class = self.current_class.replace(name.clone());
+        let enclosing_members = self.pending_members.replace(vec![]);
+        let enclosing_static = std::mem::replace(&mut self.in_static, false);
+
+        // Declare the class up front so methods can refer to it, for example to construct new
+        // instances. The symbol is updated as members get parsed.
+        self.declare_class(
+            &spanned_name,
+            &members.method_symbols,
+            &members.static_symbols,
+            &members.field_symbols,
+        );
 
         while !self.source.at_end() && !self.at(TokenKind::RightBrace) {
-            if self.at(TokenKind::Static) {
-                let static_token_span = self.bump()?.span;
-                let binding = self.binding()?;
+            let checkpoint = self.source.checkpoint();
+            let depth = self.symtab.depth();
+            let yields = self.yields.clone();
+            let returns = self.returns.clone();
+            let inferring = self.inferring.clone();
+
+            // A member with errors is skipped on its own, so the members after it still get
+            // declared and checked
+            if let Err(error) = self.class_member(&mut members) {
+                if self.source.at_end() {
+                    return Err(error);
+                }
+                self.errors.push(error);
 
-                let mut ty = binding.ty.clone();
-                let mut expr = None;
-                if self.at(TokenKind::Equal) {
-                    self.bump()?;
-                    let val = self.expr()?;
-                    if !type_compatible(&ty, &val.ty) {
-                        return Err(ParseError::IncompatibleTypes(
-                            self.source.id,
-                            binding.name.span.into(),
-                            binding.ty,
-                            val.span.into(),
-                            val.ty,
-                        ));
-                    }
+                self.symtab.truncate(depth);
+                self.in_static = false;
+                self.yields = yields;
+                self.returns = returns;
+                self.inferring = inferring;
+
+                self.source.restore(checkpoint);
+                self.skip_body()?;
+            }
 
-                    ty = val.ty.clone();
-                    expr = Some(val);
+            self.declare_class(
+                &spanned_name,
+                &members.method_symbols,
+                &members.static_symbols,
+                &members.field_symbols,
+            );
+        }
+
+        // Every member is declared now, so the ones used before their declaration can be checked
+        let object = Type::Instance(name.clone());
+        for (member, value) in self.pending_members.take().unwrap_or_default() {
+            match value {
+                Some((ty, span)) => self.check_field_assignment(&object, &member, (&ty, span))?,
+                None => {
+                    self.property_type(&object, &member, false)?;
+                }
+            }
+        }
+        self.pending_members = enclosing_members;
+
+        if let (Some(equals), None) = (
+            members.method_symbols.get(EQUALS_METHOD),
+            members.method_symbols.get(HASH_METHOD),
+        ) {
+            return Err(ParseError::MissingHashMethod(
+                self.source.id,
+                equals.name.span.into(),
+                name,
+            ));
+        }
+
+        self.current_class = enclosing_class;
+        self.in_static = enclosing_static;
+
+        let right_brace = self.expect(TokenKind::RightBrace)?;
+
+        Ok(Statement {
+            span: Span::combine(&[class_token_span, right_brace.span]),
+            stmt: Stmt::Class(Class {
+                name: Spanned {
+                    item: name,
+                    span: name_span,
+                },
+                methods: members.methods,
+                statics: members.statics,
+                fields: members.fields,
+                getters: members.getters,
+                setters: members.setters,
+            }),
+        })
+    }
+
+    fn class_member(&mut self, members: &mut ClassMembers) -> Result<(), ParseError> {
+        let ClassMembers {
+            methods,
+            method_symbols,
+            statics,
+            static_symbols,
+            fields,
+            field_symbols,
+            getters,
+            setters,
+        } = members;
+
+        let vis = self.member_visibility()?;
+
+        if self.at(TokenKind::Static) && self.source.peek_nth_kind(2) == Some(TokenKind::LeftParen)
+        {
+            // Static methods are called on the class itself, so they don't have 'this'
+            self.bump()?;
+            self.in_static = true;
+            let stmt = self.func_decl(true)?;
+            self.in_static = false;
+            if let Stmt::Function(func) = &stmt.stmt {
+                if let Some(previous) = static_symbols.get(&func.name.item) {
+                    return Err(ParseError::CannotRedeclareSymbol(
+                        self.source.id,
+                        func.name.item.clone(),
+                        func.name.span.into(),
+                        previous.name.span.into(),
+                    ));
                 }
 
                 static_symbols.insert(
-                    binding.name.item.clone(),
+                    func.name.item.clone(),
                     Symbol {
-                        sym: Sym::Variable,
-                        name: binding.name.clone(),
-                        ty,
+                        name: func.name.clone(),
+                        sym: function_sym(&func.params),
+                        ty: func.ty.ty.clone(),
+                        vis,
                     },
                 );
+            } else {
+                panic!("ICE: func_decl returned a stmt that wasnt a function");
+            }
 
-                let semi_token = self.expect(TokenKind::Semicolon)?;
-                statics.push(Statement {
-                    span: Span::combine(&[static_token_span, semi_token.span]),
-                    stmt: Stmt::Variable {
-                        name: binding,
-                        expr,
-                    },
-                });
+            statics.push(stmt);
+        } else if self.at(TokenKind::Static) {
+            let static_token_span = self.bump()?.span;
+            let binding = self.binding()?;
+
+            let mut ty = binding.ty.clone();
+            let mut expr = None;
+            if self.at(TokenKind::Equal) {
+                self.bump()?;
+                let val = self.expr()?;
+                if !type_compatible(&ty, &val.ty) {
+                    return Err(ParseError::IncompatibleTypes(
+                        self.source.id,
+                        binding.name.span.into(),
+                        binding.ty,
+                        val.span.into(),
+                        val.ty,
+                    ));
+                }
+
+                ty = val.ty.clone();
+                expr = Some(val);
+            }
+
+            static_symbols.insert(
+                binding.name.item.clone(),
+                Symbol {
+                    sym: Sym::Variable,
+                    name: binding.name.clone(),
+                    ty,
+                    vis,
+                },
+            );
+
+            let semi_token = self.expect(TokenKind::Semicolon)?;
+            statics.push(Statement {
+                span: Span::combine(&[static_token_span, semi_token.span]),
+                stmt: Stmt::Variable {
+                    name: binding,
+                    expr,
+                },
+            });
+        } else if self.at(TokenKind::Ident)
+            && matches!(
+                self.source.peek_nth_kind(1),
+                Some(TokenKind::Colon | TokenKind::Equal | TokenKind::Semicolon)
+            )
+        {
+            let (stmt, mut symbol) = self.field_decl()?;
+            symbol.vis = vis;
+
+            let previous = field_symbols
+                .get(&symbol.name.item)
+                .or_else(|| method_symbols.get(&symbol.name.item));
+            if let Some(previous) = previous {
+                return Err(ParseError::CannotRedeclareSymbol(
+                    self.source.id,
+                    symbol.name.item.clone(),
+                    symbol.name.span.into(),
+                    previous.name.span.into(),
+                ));
+            }
+
+            fields.push(stmt);
+            field_symbols.insert(symbol.name.item.clone(), symbol);
+        } else if self.at_accessor() {
+            let getter = self.bump()?.text == "get";
+            let stmt = self.func_decl(true)?;
+            let func = match &stmt.stmt {
+                Stmt::Function(func) => func,
+                _ => panic!("ICE: func_decl returned a stmt that wasnt a function"),
+            };
+
+            // The property has the type the getter returns, or the setter takes
+            let ty = if getter {
+                if !func.params.is_empty() {
+                    return Err(ParseError::InvalidGetter(
+                        self.source.id,
+                        func.name.span.into(),
+                    ));
+                }
+
+                match &func.ty.ty {
+                    Type::Function { returns, .. } => returns.ty.clone(),
+                    _ => panic!("ICE: function type is not Type::Function"),
+                }
             } else {
-                let stmt = self.func_decl(true)?;
-                match stmt.clone().stmt {
-                    Stmt::Function(func) => {
-                        methods.push(stmt);
-                        method_symbols.insert(
+                match func.params.as_slice() {
+                    [param] if !param.rest => param.ty.clone(),
+                    _ => {
+                        return Err(ParseError::InvalidSetter(
+                            self.source.id,
+                            func.name.span.into(),
+                        ))
+                    }
+                }
+            };
+
+            // A getter and a setter with the same name make up one property
+            match field_symbols.get_mut(&func.name.item) {
+                Some(Symbol {
+                    sym: Sym::Property { get, set },
+                    ty: property_ty,
+                    ..
+                }) if (getter && !*get) || (!getter && !*set) => {
+                    if !type_compatible(property_ty, &ty) {
+                        return Err(ParseError::IncompatibleTypes(
+                            self.source.id,
+                            func.name.span.into(),
+                            property_ty.clone(),
+                            func.name.span.into(),
+                            ty,
+                        ));
+                    }
+
+                    *get |= getter;
+                    *set |= !getter;
+                }
+                _ => {
+                    let previous = field_symbols
+                        .get(&func.name.item)
+                        .or_else(|| method_symbols.get(&func.name.item));
+                    if let Some(previous) = previous {
+                        return Err(ParseError::CannotRedeclareSymbol(
+                            self.source.id,
                             func.name.item.clone(),
-                            Symbol {
-                                name: func.name.clone(),
-                                sym: Sym::Function {
-                                    arity: func.params.len(),
-                                },
-                                ty: func.ty.ty,
+                            func.name.span.into(),
+                            previous.name.span.into(),
+                        ));
+                    }
+
+                    field_symbols.insert(
+                        func.name.item.clone(),
+                        Symbol {
+                            name: func.name.clone(),
+                            sym: Sym::Property {
+                                get: getter,
+                                set: !getter,
                             },
-                        );
+                            ty,
+                            vis,
+                        },
+                    );
+                }
+            }
+
+            if getter {
+                getters.push(stmt);
+            } else {
+                setters.push(stmt);
+            }
+        } else {
+            let stmt = self.func_decl(true)?;
+            match stmt.clone().stmt {
+                Stmt::Function(func) => {
+                    if func.name.item.starts_with("op_") && func.params.len() != 1 {
+                        return Err(ParseError::InvalidOperatorOverload(
+                            self.source.id,
+                            func.name.span.into(),
+                            func.params.len(),
+                        ));
+                    }
+                    self.check_protocol_method(&func)?;
+
+                    let symbol = Symbol {
+                        name: func.name.clone(),
+                        sym: function_sym(&func.params),
+                        ty: func.ty.ty.clone(),
+                        vis,
+                    };
+
+                    match method_symbols.get_mut(&func.name.item) {
+                        Some(primary) => {
+                            if !same_params(&primary.ty, &symbol.ty)
+                                && !overloadable_method(&func.name.item)
+                            {
+                                return Err(ParseError::InvalidOverload(
+                                    self.source.id,
+                                    func.name.span.into(),
+                                    func.name.item,
+                                ));
+                            }
+
+                            let mut func = func;
+                            func.name.item = add_overload(self.source.id, primary, symbol)?;
+                            self.overloaded_methods.insert(primary.name.item.clone());
+                            methods.push(Statement {
+                                stmt: Stmt::Function(func),
+                                span: stmt.span,
+                            });
+                        }
+                        None => {
+                            // Fields and methods are both properties of the instance
+                            if let Some(previous) = field_symbols.get(&func.name.item) {
+                                return Err(ParseError::CannotRedeclareSymbol(
+                                    self.source.id,
+                                    func.name.item,
+                                    func.name.span.into(),
+                                    previous.name.span.into(),
+                                ));
+                            }
+
+                            methods.push(stmt);
+                            method_symbols.insert(func.name.item.clone(), symbol);
+                        }
                     }
-                    _ => panic!("ICE: func_decl returned a stmt that wasnt a function"),
                 }
+                _ => panic!("ICE: func_decl returned a stmt that wasnt a function"),
+            }
+        }
+
+        Ok(())
+    }
+
+    // 'get' and 'set' are only keywords in front of a method, so they can still be used as names
+    fn at_accessor(&mut self) -> bool {
+        matches!(self.source.peek_text(), Some("get" | "set"))
+            && self.source.peek_nth_kind(1) == Some(TokenKind::Ident)
+            && self.source.peek_nth_kind(2) == Some(TokenKind::LeftParen)
+    }
+
+    fn member_visibility(&mut self) -> Result<Visibility, ParseError> {
+        if self.at(TokenKind::Priv) {
+            self.bump()?;
+            Ok(Visibility::Private)
+        } else {
+            if self.at(TokenKind::Pub) {
+                self.bump()?;
             }
+
+            Ok(Visibility::Public)
         }
+    }
 
+    fn declare_class(
+        &mut self,
+        name: &Spanned<String>,
+        methods: &HashMap<String, Symbol>,
+        statics: &HashMap<String, Symbol>,
+        fields: &HashMap<String, Symbol>,
+    ) {
         self.symtab.insert(Symbol {
-            name: spanned_name,
-            ty: Type::Class(name.clone()),
+            name: name.clone(),
+            ty: Type::Class(name.item.clone()),
+            vis: Visibility::Private,
             sym: Sym::Class {
-                methods: method_symbols,
-                statics: static_symbols,
+                methods: methods.clone(),
+                statics: statics.clone(),
+                fields: fields.clone(),
             },
         });
+    }
 
-        let right_brace = self.expect(TokenKind::RightBrace)?;
+    // Fields live on instances, so unlike variables they can share names with other symbols
+    fn field_decl(&mut self) -> Result<(Statement, Symbol), ParseError> {
+        trace!("parse_field_decl");
+        let name: Spanned<String> = self.expect(TokenKind::Ident)?.into();
 
-        Ok(Statement {
-            span: Span::combine(&[class_token_span, right_brace.span]),
-            stmt: Stmt::Class(Class {
-                name: Spanned {
-                    item: name,
-                    span: name_span,
+        let mut annotation = None;
+        if self.at(TokenKind::Colon) {
+            self.bump()?;
+            annotation = Some(self.ty()?);
+        }
+
+        let mut expr = None;
+        if self.at(TokenKind::Equal) {
+            self.bump()?;
+            self.in_field_initializer = true;
+            let val = self.expr();
+            self.in_field_initializer = false;
+            let val = val?;
+
+            if let Some(annotation) = &annotation {
+                if !type_compatible(&annotation.ty, &val.ty) {
+                    return Err(ParseError::IncompatibleTypes(
+                        self.source.id,
+                        annotation.span.into(),
+                        annotation.ty.clone(),
+                        val.span.into(),
+                        val.ty,
+                    ));
+                }
+            }
+
+            expr = Some(val);
+        }
+
+        // The annotation wins over the initializer, so 'next: Node = null' isn't typed as null
+        let ty = match (annotation, &expr) {
+            (Some(annotation), _) => annotation.ty,
+            (None, Some(expr)) => expr.ty.clone(),
+            (None, None) => Type::Any,
+        };
+
+        let semi_token = self.expect(TokenKind::Semicolon)?;
+
+        Ok((
+            Statement {
+                span: Span::combine(&[name.span, semi_token.span]),
+                stmt: Stmt::Variable {
+                    name: Binding {
+                        name: name.clone(),
+                        ty: ty.clone(),
+                    },
+                    expr,
                 },
-                methods,
-                statics,
-            }),
-        })
+            },
+            Symbol {
+                name,
+                sym: Sym::Variable,
+                ty,
+                vis: Visibility::Public,
+            },
+        ))
     }
 
     fn func_decl(&mut self, from_class_decl: bool) -> Result<Statement, ParseError> {
@@ -328,7 +1801,7 @@ impl Parser {
         }
 
         let name_token = self.expect(TokenKind::Ident)?;
-        let spanned_name = Spanned {
+        let mut spanned_name = Spanned {
             item: name_token.text.to_string(),
             span: name_token.span,
         };
@@ -340,10 +1813,26 @@ impl Parser {
 
         self.expect(TokenKind::LeftParen)?;
 
-        let mut params = Vec::new();
+        // Params get their own scope while being parsed so default values can use earlier params
+        self.symtab.level_up();
+
+        let mut params: Vec<Param> = Vec::new();
         if !self.at(TokenKind::RightParen) {
             loop {
-                let param = self.binding()?;
+                if let Some(rest) = params.last().filter(|param| param.rest) {
+                    return Err(ParseError::InvalidRestParameter(
+                        self.source.id,
+                        rest.name.span.into(),
+                    ));
+                }
+
+                let param = self.param(params.last())?;
+                self.symtab.insert(Symbol {
+                    name: param.name.clone(),
+                    sym: Sym::Variable,
+                    ty: param.ty.clone(),
+                    vis: Visibility::Private,
+                });
                 params.push(param);
 
                 if self.at(TokenKind::Comma) {
@@ -354,6 +1843,8 @@ impl Parser {
             }
         }
 
+        self.symtab.level_down();
+
         let right_paren_span = self.expect(TokenKind::RightParen)?.span;
 
         // Check if type defined
@@ -362,28 +1853,40 @@ impl Parser {
             ty: Type::Any,
         };
 
-        if self.at(TokenKind::Arrow) {
+        let annotated = self.at(TokenKind::Arrow);
+        if annotated {
             self.bump()?;
             return_ty = self.ty()?;
         }
 
-        let func_type = Type::Function {
+        // Constructors always return their instance
+        let infer_return = !annotated && (!from_class_decl || name != "constructor");
+
+        // Generators are declared as generators before their body is parsed, so that they can
+        // loop over calls to themselves
+        let mut returns = return_ty.clone();
+        if self.body_yields() {
+            returns.ty = Type::Generator(Box::new(return_ty.clone()));
+        }
+
+        let mut func_type = Type::Function {
             params: params
                 .clone()
                 .into_iter()
                 .map(|param| param.into())
                 .collect(),
-            returns: Box::new(return_ty.clone()),
+            returns: Box::new(returns),
+            variadic: params.last().is_some_and(|param| param.rest),
         };
 
+        // Class methods are declared by the class, which handles their overloads itself
         if !from_class_decl {
-            self.symtab.insert(Symbol {
+            spanned_name.item = self.declare_function(Symbol {
                 name: spanned_name.clone(),
-                sym: Sym::Function {
-                    arity: params.len(),
-                },
+                sym: function_sym(&params),
                 ty: func_type.clone(),
-            });
+                vis: Visibility::Private,
+            })?;
         }
 
         self.symtab.level_up();
@@ -393,118 +1896,356 @@ impl Parser {
                 name: param.name.clone(),
                 sym: Sym::Variable,
                 ty: param.ty.clone(),
+                vis: Visibility::Private,
             })
         });
 
+        // Only collect the yields of this body, and not of the functions declared inside of it
+        let enclosing_yields = self.yields.replace(vec![]);
+        let enclosing_returns = self.returns.replace(vec![]);
+        let enclosing_inferring = std::mem::replace(
+            &mut self.inferring,
+            infer_return.then(|| spanned_name.item.clone()),
+        );
+        let errors = self.errors.len();
         let body = self.block(true)?;
+        let yields = std::mem::replace(&mut self.yields, enclosing_yields).unwrap_or_default();
+        let returns = std::mem::replace(&mut self.returns, enclosing_returns).unwrap_or_default();
+        self.inferring = enclosing_inferring;
 
         self.symtab.level_down();
 
-        if let Stmt::Block(stmts) = &body.stmt {
-            // If the body has a return statement in it, make sure the types line up
-            if let Some(Statement {
-                stmt: Stmt::Return(ret),
-                ..
-            }) = stmts.last()
-            {
-                if let Some(ret_expr) = ret {
-                    if !type_compatible(&ret_expr.ty, &return_ty.ty) {
-                        return Err(ParseError::IncompatibleTypes(
-                            self.source.id,
-                            return_ty.span.into(),
-                            return_ty.ty,
-                            ret_expr.span.into(),
-                            ret_expr.ty.clone(),
-                        ));
-                    } else {
-                        // Update type to the type of the return stmt
-                        if !from_class_decl {
-                            let sym = self
-                                .symtab
-                                .lookup_mut(&name)
-                                .expect("ICE: couldn't find func symbol to update ret type");
-                            if let Type::Function { params, returns } = sym.ty.clone() {
-                                sym.ty = Type::Function {
-                                    params,
-                                    returns: Box::new(TypeExpression {
-                                        span: returns.span,
-                                        ty: return_ty.ty,
-                                    }),
-                                };
-                            } else {
-                                panic!("ICE: function type in symtab is not Type::Function");
-                            }
-                        }
-                    }
+        // Statements with errors were skipped, so the return type of the body can't be trusted
+        let body_has_errors = self.errors.len() > errors;
+
+        let generator = !yields.is_empty();
+        if generator {
+            // The value of a return would never reach the caller, which only sees null once the
+            // generator finishes
+            if let Some(ret) = returns.iter().find(|ret| ret.ty != Type::Null) {
+                return Err(ParseError::GeneratorReturnsValue(
+                    self.source.id,
+                    ret.span.into(),
+                ));
+            }
+
+            // The return type annotation of a generator is the type of the values it yields
+            for yielded in &yields {
+                if !type_compatible(&return_ty.ty, &yielded.ty) {
+                    return Err(ParseError::IncompatibleTypes(
+                        self.source.id,
+                        return_ty.span.into(),
+                        return_ty.ty,
+                        yielded.span.into(),
+                        yielded.ty.clone(),
+                    ));
+                }
+            }
+
+            let mut yield_ty = return_ty.clone();
+            if yield_ty.ty == Type::Any && yields.iter().all(|yielded| yielded.ty == yields[0].ty) {
+                yield_ty.ty = yields[0].ty.clone();
+            }
+
+            if let Type::Function { returns, .. } = &mut func_type {
+                returns.ty = Type::Generator(Box::new(yield_ty));
+            }
+
+            if !from_class_decl {
+                self.function_symbol_mut(&name, &spanned_name.item).ty = func_type.clone();
+            }
+        } else if !body_has_errors {
+            for ret in &returns {
+                if !type_compatible(&return_ty.ty, &ret.ty) {
+                    return Err(ParseError::IncompatibleTypes(
+                        self.source.id,
+                        return_ty.span.into(),
+                        return_ty.ty,
+                        ret.span.into(),
+                        ret.ty.clone(),
+                    ));
+                }
+            }
+
+            // Falling off the end of the body returns null, which the return type has to allow
+            let falls_through = !always_returns(&body);
+            if !type_compatible(&return_ty.ty, &Type::Null) && falls_through {
+                return Err(ParseError::FunctionHasIncompatibleReturnType(
+                    self.source.id,
+                    return_ty.span.into(),
+                    return_ty.ty,
+                    body.span.past().into(),
+                    Type::Null,
+                ));
+            }
+
+            // Without an annotation, the function returns whatever its returns do
+            if infer_return {
+                let mut returns = returns;
+                if falls_through {
+                    returns.push(TypeExpression {
+                        ty: Type::Null,
+                        span: body.span.past(),
+                    });
+                }
+
+                if let Type::Function { returns: ty, .. } = &mut func_type {
+                    ty.ty = unify_types(returns);
+                }
+
+                if !from_class_decl {
+                    self.function_symbol_mut(&name, &spanned_name.item).ty = func_type.clone();
+                }
+            }
+        }
+
+        Ok(Statement {
+            stmt: Stmt::Function(Function {
+                ty: TypeExpression {
+                    ty: func_type,
+                    span: spanned_name.span,
+                },
+                name: spanned_name,
+                params,
+                body: Box::new(body),
+                generator,
+            }),
+            span: Span::combine(&[start_span, right_paren_span]),
+        })
+    }
+
+    fn var_decl(&mut self) -> Result<Statement, ParseError> {
+        trace!("parse_var_decl");
+        let let_token_span = self.bump()?.span;
+
+        if self.at_set(&[TokenKind::LeftBracket, TokenKind::LeftBrace]) {
+            return self.destructure(let_token_span);
+        }
+
+        let binding = self.binding()?;
+
+        let mut ty = binding.ty.clone();
+        let mut expr = None;
+        if self.at(TokenKind::Equal) {
+            self.bump()?;
+            let val = match self.var_initializer(&binding) {
+                Ok(val) => val,
+                Err(error) => {
+                    // Still declare the variable, so its later uses aren't reported as well
+                    self.symtab.insert(Symbol {
+                        sym: Sym::Variable,
+                        name: binding.name.clone(),
+                        ty: binding.ty,
+                        vis: Visibility::Private,
+                    });
+                    return Err(error);
                 }
+            };
+
+            // Variables declared with a union type keep it, so any of its types can be assigned
+            if !matches!(ty, Type::Union(..)) {
+                ty = val.ty.clone();
+            }
+            expr = Some(val);
+        }
+
+        self.symtab.insert(Symbol {
+            sym: Sym::Variable,
+            name: binding.name.clone(),
+            ty,
+            vis: Visibility::Private,
+        });
+
+        let semi_token = self.expect(TokenKind::Semicolon)?;
+        Ok(Statement {
+            span: Span::combine(&[let_token_span, semi_token.span]),
+            stmt: Stmt::Variable {
+                name: binding,
+                expr,
+            },
+        })
+    }
+
+    fn var_initializer(&mut self, binding: &Binding) -> Result<Expression, ParseError> {
+        if let Type::List(element) = &binding.ty {
+            if self.at(TokenKind::LeftBracket) {
+                self.list_element = Some(*element.clone());
+            }
+        }
+
+        let val = self.expr()?;
+        if !type_compatible(&binding.ty, &val.ty) {
+            return Err(ParseError::IncompatibleTypes(
+                self.source.id,
+                binding.name.span.into(),
+                binding.ty.clone(),
+                val.span.into(),
+                val.ty,
+            ));
+        }
+
+        Ok(val)
+    }
+
+    fn destructure(&mut self, let_token_span: Span) -> Result<Statement, ParseError> {
+        trace!("parse_destructure");
+        let (close, list) = match self.bump()?.kind {
+            TokenKind::LeftBracket => (TokenKind::RightBracket, true),
+            _ => (TokenKind::RightBrace, false),
+        };
+
+        let mut bindings: Vec<Binding> = Vec::new();
+        loop {
+            let binding = self.binding()?;
+            if let Some(previous) = bindings
+                .iter()
+                .find(|previous| previous.name.item == binding.name.item)
+            {
+                return Err(ParseError::CannotRedeclareSymbol(
+                    self.source.id,
+                    binding.name.item.clone(),
+                    binding.name.span.into(),
+                    previous.name.span.into(),
+                ));
+            }
+            bindings.push(binding);
+
+            if self.at(TokenKind::Comma) {
+                self.bump()?;
             } else {
-                // If the body has no return statement, make sure there is no return type
-                // annotation on the function
-                if !matches!(return_ty.ty, Type::Any | Type::Null) {
-                    return Err(ParseError::FunctionHasIncompatibleReturnType(
-                        self.source.id,
-                        return_ty.span.into(),
-                        return_ty.ty,
-                        body.span.past().into(),
-                        Type::Null,
-                    ));
+                break;
+            }
+        }
+
+        self.expect(close)?;
+        self.expect(TokenKind::Equal)?;
+        let (expr, tys) = match self.destructured_types(&bindings, list) {
+            Ok(typed) => typed,
+            Err(error) => {
+                // Still declare the bindings, so their later uses aren't reported as well
+                for binding in bindings {
+                    self.symtab.insert(Symbol {
+                        sym: Sym::Variable,
+                        name: binding.name,
+                        ty: binding.ty,
+                        vis: Visibility::Private,
+                    });
                 }
+                return Err(error);
             }
+        };
+
+        for (binding, ty) in bindings.iter().zip(tys) {
+            self.symtab.insert(Symbol {
+                sym: Sym::Variable,
+                name: binding.name.clone(),
+                ty,
+                vis: Visibility::Private,
+            });
         }
 
+        let semi_token = self.expect(TokenKind::Semicolon)?;
         Ok(Statement {
-            stmt: Stmt::Function(Function {
-                ty: TypeExpression {
-                    ty: func_type,
-                    span: spanned_name.span,
+            span: Span::combine(&[let_token_span, semi_token.span]),
+            stmt: Stmt::Destructure {
+                pattern: if list {
+                    Pattern::List(bindings)
+                } else {
+                    Pattern::Fields(bindings)
                 },
-                name: spanned_name,
-                params,
-                body: Box::new(body),
-            }),
-            span: Span::combine(&[start_span, right_paren_span]),
+                expr,
+            },
         })
     }
 
-    fn var_decl(&mut self) -> Result<Statement, ParseError> {
-        trace!("parse_var_decl");
-        let let_token_span = self.bump()?.span;
+    // Parses the value being destructured, and finds the type of each binding from it
+    fn destructured_types(
+        &mut self,
+        bindings: &[Binding],
+        list: bool,
+    ) -> Result<(Expression, Vec<Type>), ParseError> {
+        let expr = self.expr()?;
 
-        let binding = self.binding()?;
+        if let (Type::Tuple(elems), true) = (&expr.ty, list) {
+            if elems.len() != bindings.len() {
+                return Err(ParseError::TupleLengthMismatch(
+                    self.source.id,
+                    expr.span.into(),
+                    elems.len(),
+                    expr.ty.clone(),
+                ));
+            }
+        }
 
-        let mut ty = binding.ty.clone();
-        let mut expr = None;
-        if self.at(TokenKind::Equal) {
-            self.bump()?;
-            let val = self.expr()?;
-            if !type_compatible(&ty, &val.ty) {
+        // Find the type of each binding, from the list element type, the tuple element types or
+        // the class field types
+        let mut tys = Vec::with_capacity(bindings.len());
+        for (i, binding) in bindings.iter().enumerate() {
+            let ty = match (&expr.ty, list) {
+                (Type::Any, _) => Type::Any,
+                (Type::List(elem), true) => elem.ty.clone(),
+                (Type::Tuple(elems), true) => elems[i].ty.clone(),
+                (Type::Instance(class_name), false) => {
+                    let field = match self.symtab.lookup(class_name) {
+                        Some(Symbol {
+                            sym: Sym::Class { fields, .. },
+                            ..
+                        }) => fields.get(&binding.name.item),
+                        _ => None,
+                    };
+
+                    match field {
+                        // Getters are called to destructure their property
+                        Some(Symbol {
+                            sym: Sym::Property { get: false, .. },
+                            ..
+                        }) => {
+                            return Err(ParseError::WriteOnlyProperty(
+                                self.source.id,
+                                binding.name.span.into(),
+                                binding.name.item.clone(),
+                            ))
+                        }
+                        Some(field) => {
+                            self.check_visible(class_name, field, &binding.name)?;
+                            field.ty.clone()
+                        }
+                        None => {
+                            return Err(ParseError::UnknownField(
+                                self.source.id,
+                                binding.name.span.into(),
+                                expr.ty.clone(),
+                                binding.name.item.clone(),
+                            ))
+                        }
+                    }
+                }
+                _ => {
+                    return Err(ParseError::CannotDestructure(
+                        self.source.id,
+                        expr.span.into(),
+                        expr.ty.clone(),
+                    ))
+                }
+            };
+
+            if !type_compatible(&binding.ty, &ty) {
                 return Err(ParseError::IncompatibleTypes(
                     self.source.id,
                     binding.name.span.into(),
-                    binding.ty,
-                    val.span.into(),
-                    val.ty,
+                    binding.ty.clone(),
+                    expr.span.into(),
+                    ty,
                 ));
             }
 
-            ty = val.ty.clone();
-            expr = Some(val);
+            tys.push(match &binding.ty {
+                Type::Any => ty,
+                annotated => annotated.clone(),
+            });
         }
 
-        self.symtab.insert(Symbol {
-            sym: Sym::Variable,
-            name: binding.name.clone(),
-            ty,
-        });
-
-        let semi_token = self.expect(TokenKind::Semicolon)?;
-        Ok(Statement {
-            span: Span::combine(&[let_token_span, semi_token.span]),
-            stmt: Stmt::Variable {
-                name: binding,
-                expr,
-            },
-        })
+        Ok((expr, tys))
     }
 
     fn stmt(&mut self) -> Result<Statement, ParseError> {
@@ -515,6 +2256,10 @@ impl Parser {
             self.if_stmt()
         } else if self.at(TokenKind::Until) {
             self.until_stmt()
+        } else if self.at(TokenKind::For) {
+            self.for_stmt()
+        } else if self.at(TokenKind::Yield) {
+            self.yield_stmt()
         } else if self.at(TokenKind::Ret) {
             self.ret_stmt()
         } else {
@@ -535,11 +2280,34 @@ impl Parser {
 
         let mut expr: Option<Expression> = None;
         if !self.at(TokenKind::Semicolon) {
+            self.recursive_call = None;
             expr = Some(self.expr()?);
+
+            // The type of the value would depend on the type that is being inferred
+            if let (Some(call), Some(name)) = (self.recursive_call.take(), &self.inferring) {
+                return Err(ParseError::CyclicReturnType(
+                    self.source.id,
+                    call.into(),
+                    name.clone(),
+                ));
+            }
         }
 
         let semi_colon_span = self.bump()?.span;
 
+        if let Some(returns) = &mut self.returns {
+            returns.push(match &expr {
+                Some(expr) => TypeExpression {
+                    ty: expr.ty.clone(),
+                    span: expr.span,
+                },
+                None => TypeExpression {
+                    ty: Type::Null,
+                    span: ret_span,
+                },
+            });
+        }
+
         Ok(Statement {
             stmt: Stmt::Return(expr),
             span: Span::combine(&[ret_span, semi_colon_span]),
@@ -563,6 +2331,135 @@ impl Parser {
         })
     }
 
+    fn for_stmt(&mut self) -> Result<Statement, ParseError> {
+        trace!("parse_for_stmt");
+        let for_token_span = self.expect(TokenKind::For)?.span;
+
+        // The loop variable only exists inside of the body, so it can shadow outer variables. It
+        // isn't declared until after the iterable, which can't use it.
+        self.symtab.level_up();
+        let binding = self.binding()?;
+        self.expect(TokenKind::In)?;
+        let iterable = self.expr()?;
+        self.declare_loop_variable(&binding, &iterable)?;
+
+        let body = self.stmt()?;
+
+        self.symtab.level_down();
+
+        Ok(Statement {
+            span: Span::combine(&[for_token_span, body.span]),
+            stmt: Stmt::For {
+                binding,
+                iterable,
+                body: Box::new(body),
+            },
+        })
+    }
+
+    fn yield_stmt(&mut self) -> Result<Statement, ParseError> {
+        trace!("parse_yield_stmt");
+        let yield_span = self.expect(TokenKind::Yield)?.span;
+
+        let expr = self.expr()?;
+        let semi_colon_span = self.expect(TokenKind::Semicolon)?.span;
+
+        match &mut self.yields {
+            Some(yields) => yields.push(TypeExpression {
+                ty: expr.ty.clone(),
+                span: expr.span,
+            }),
+            None => {
+                return Err(ParseError::YieldOutsideFunction(
+                    self.source.id,
+                    yield_span.into(),
+                ))
+            }
+        }
+
+        Ok(Statement {
+            stmt: Stmt::Yield(expr),
+            span: Span::combine(&[yield_span, semi_colon_span]),
+        })
+    }
+
+    // Type of the values produced by looping over an expression
+    fn declare_loop_variable(
+        &mut self,
+        binding: &Binding,
+        iterable: &Expression,
+    ) -> Result<(), ParseError> {
+        let ty = self.iterable_type(iterable)?;
+        if !type_compatible(&binding.ty, &ty) {
+            return Err(ParseError::IncompatibleTypes(
+                self.source.id,
+                binding.name.span.into(),
+                binding.ty.clone(),
+                iterable.span.into(),
+                ty,
+            ));
+        }
+
+        self.symtab.insert(Symbol {
+            name: binding.name.clone(),
+            sym: Sym::Variable,
+            ty: match binding.ty {
+                Type::Any => ty,
+                ref annotated => annotated.clone(),
+            },
+            vis: Visibility::Private,
+        });
+
+        Ok(())
+    }
+
+    fn iterable_type(&self, iterable: &Expression) -> Result<Type, ParseError> {
+        self.iterated_type(&iterable.ty).ok_or_else(|| {
+            ParseError::NotIterable(self.source.id, iterable.span.into(), iterable.ty.clone())
+        })
+    }
+
+    // Instances are iterable when their class has an 'iter' method without params. It can return
+    // a list or generator, or an iterator instance whose 'next' method returns null once it is
+    // done. Classes that aren't iterable can use those names for anything else.
+    fn iterated_type(&self, ty: &Type) -> Option<Type> {
+        match ty {
+            Type::List(ty) | Type::Generator(ty) => Some(ty.ty.clone()),
+            Type::Any => Some(Type::Any),
+            Type::Instance(class_name) => {
+                match self.iterator_method_type(class_name, ITER_METHOD)? {
+                    Type::Instance(iterator) => {
+                        match self.iterator_method_type(&iterator, NEXT_METHOD)? {
+                            Type::Union(types) => {
+                                let mut types = types
+                                    .into_iter()
+                                    .filter(|ty| ty.ty != Type::Null)
+                                    .collect::<Vec<_>>();
+                                match types.len() {
+                                    1 => types.pop().map(|ty| ty.ty),
+                                    _ => Some(Type::Union(types)),
+                                }
+                            }
+                            ty => Some(ty),
+                        }
+                    }
+                    ty => self.iterated_type(&ty),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn iterator_method_type(&self, class_name: &str, method: &str) -> Option<Type> {
+        match &self.class_method(class_name, method)?.ty {
+            Type::Function {
+                params, returns, ..
+            } if params.is_empty() => Some(returns.ty.clone()),
+            Type::Function { .. } => None,
+            _ => Some(Type::Any),
+        }
+    }
+
     fn if_stmt(&mut self) -> Result<Statement, ParseError> {
         trace!("parse_if_stmt");
         let if_token_span = self.expect(TokenKind::If)?.span;
@@ -571,8 +2468,16 @@ impl Parser {
         let cond = self.expr()?;
         //self.expect(TokenKind::RightParen)?;
 
+        let narrowed = self.narrowed(&cond);
+        self.symtab.level_up();
+        if let Some(symbol) = narrowed {
+            self.symtab.insert(symbol);
+        }
+
         let body = self.stmt()?;
 
+        self.symtab.level_down();
+
         let mut else_branch = None;
         if self.at(TokenKind::Else) {
             self.bump()?;
@@ -589,6 +2494,27 @@ impl Parser {
         })
     }
 
+    // 'if x is int' narrows the type of the variable to int inside of the body
+    fn narrowed(&self, cond: &Expression) -> Option<Symbol> {
+        if let Expr::Is { expr, ty } = &cond.expr {
+            if let Expr::Variable(name) = &expr.expr {
+                if let Some(
+                    symbol @ Symbol {
+                        sym: Sym::Variable, ..
+                    },
+                ) = self.symtab.lookup(name)
+                {
+                    return Some(Symbol {
+                        ty: ty.ty.clone(),
+                        ..symbol.clone()
+                    });
+                }
+            }
+        }
+
+        None
+    }
+
     fn block(&mut self, from_func_decl: bool) -> Result<Statement, ParseError> {
         trace!("parse_block");
         let left_brace_span = self.expect(TokenKind::LeftBrace)?.span;
@@ -599,7 +2525,7 @@ impl Parser {
 
         let mut stmts = Vec::new();
         while !self.source.at_end() && !self.at(TokenKind::RightBrace) {
-            stmts.push(self.decl()?);
+            stmts.extend(self.decl_or_recover()?);
         }
 
         let right_brace_span = self.expect(TokenKind::RightBrace)?.span;
@@ -614,6 +2540,143 @@ impl Parser {
         })
     }
 
+    // The else branch can only be left out when the if is used as a statement, in which case
+    // its value is null
+    fn if_expr(&mut self, if_token_span: Span, needs_else: bool) -> Result<Expression, ParseError> {
+        trace!("parse_if_expr");
+        let cond = self.expr()?;
+
+        let narrowed = self.narrowed(&cond);
+        self.symtab.level_up();
+        if let Some(symbol) = narrowed {
+            self.symtab.insert(symbol);
+        }
+
+        let left_brace_span = self.expect(TokenKind::LeftBrace)?.span;
+        let body = self.block_expr(left_brace_span)?;
+
+        self.symtab.level_down();
+
+        if !self.at(TokenKind::Else) {
+            if needs_else {
+                return Err(ParseError::MissingElseBranch(
+                    self.source.id,
+                    Span::combine(&[if_token_span, body.span]).into(),
+                ));
+            }
+
+            return Ok(Expression {
+                span: Span::combine(&[if_token_span, body.span]),
+                ty: Type::Null,
+                expr: Expr::If {
+                    cond: Box::new(cond),
+                    body: Box::new(body),
+                    else_branch: None,
+                },
+            });
+        }
+        self.bump()?;
+
+        // 'else if' chains nest another if expression
+        let else_branch = if self.at(TokenKind::If) {
+            let if_token_span = self.bump()?.span;
+            self.if_expr(if_token_span, needs_else)?
+        } else {
+            let left_brace_span = self.expect(TokenKind::LeftBrace)?.span;
+            self.block_expr(left_brace_span)?
+        };
+
+        // Both branches have to agree on the type of the value
+        if !type_compatible(&body.ty, &else_branch.ty) {
+            return Err(ParseError::IncompatibleTypes(
+                self.source.id,
+                body.span.into(),
+                body.ty,
+                else_branch.span.into(),
+                else_branch.ty,
+            ));
+        }
+
+        let ty = match (&body.ty, &else_branch.ty) {
+            (Type::Any | Type::Null, ty) => ty.clone(),
+            (Type::Int, Type::Float) => Type::Float,
+            (ty, _) => ty.clone(),
+        };
+
+        Ok(Expression {
+            span: Span::combine(&[if_token_span, else_branch.span]),
+            ty,
+            expr: Expr::If {
+                cond: Box::new(cond),
+                body: Box::new(body),
+                else_branch: Some(Box::new(else_branch)),
+            },
+        })
+    }
+
+    // Like block, but a trailing expression without a semicolon becomes the value of the block
+    fn block_expr(&mut self, left_brace_span: Span) -> Result<Expression, ParseError> {
+        trace!("parse_block_expr");
+        self.symtab.level_up();
+
+        // Only the statements of a function body can yield
+        let yields = self.yields.take();
+
+        let mut stmts = Vec::new();
+        let mut value = None;
+        while !self.source.at_end() && !self.at(TokenKind::RightBrace) {
+            if self.at_set(&[
+                TokenKind::Class,
+                TokenKind::Func,
+                TokenKind::Let,
+                TokenKind::Enum,
+                TokenKind::Pub,
+                TokenKind::Priv,
+                TokenKind::Until,
+                TokenKind::For,
+                TokenKind::Yield,
+                TokenKind::Ret,
+            ]) {
+                stmts.push(self.decl()?);
+                continue;
+            }
+
+            // Ifs and blocks don't need a semicolon when they're used as statements
+            let needs_semicolon = !self.at_set(&[TokenKind::If, TokenKind::LeftBrace]);
+            let expr = if self.at(TokenKind::If) {
+                let if_token_span = self.bump()?.span;
+                self.if_expr(if_token_span, false)?
+            } else {
+                self.expr()?
+            };
+
+            if self.at(TokenKind::RightBrace) {
+                value = Some(Box::new(expr));
+                break;
+            }
+
+            let mut span = expr.span;
+            if needs_semicolon || self.at(TokenKind::Semicolon) {
+                span = Span::combine(&[span, self.expect(TokenKind::Semicolon)?.span]);
+            }
+            stmts.push(Statement {
+                span,
+                stmt: Stmt::Expr(expr),
+            });
+        }
+
+        let right_brace_span = self.expect(TokenKind::RightBrace)?.span;
+
+        self.yields = yields;
+        self.symtab.level_down();
+
+        Ok(Expression {
+            span: Span::combine(&[left_brace_span, right_brace_span]),
+            ty: value.as_ref().map_or(Type::Null, |value| value.ty.clone()),
+            expr: Expr::Block { stmts, value },
+        })
+    }
+
     fn expr_stmt(&mut self) -> Result<Statement, ParseError> {
         trace!("parse_expr_stmt");
         let expr = self.expr()?;
@@ -631,7 +2694,7 @@ impl Parser {
 
     fn assignment(&mut self) -> Result<Expression, ParseError> {
         trace!("parse_assignment");
-        let expr = self.or()?;
+        let expr = self.pipe()?;
 
         if self.at(TokenKind::Equal) {
             let error_source = self.source.id;
@@ -643,7 +2706,9 @@ impl Parser {
                 Expr::Variable(name) => {
                     if let Some(entry) = self.symtab.lookup_mut(&name) {
                         if type_compatible(&entry.ty, &rhs.ty) {
-                            (*entry).ty = rhs.ty.clone();
+                            if !matches!(entry.ty, Type::Union(..)) {
+                                entry.ty = rhs.ty.clone();
+                            }
 
                             Ok(Expression {
                                 ty: rhs.ty.clone(),
@@ -673,15 +2738,19 @@ impl Parser {
                         ))
                     }
                 }
-                Expr::Get { object, name } => Ok(Expression {
-                    span: Span::combine(&[expr.span, rhs.span]),
-                    expr: Expr::Set {
-                        object,
-                        name,
-                        rhs: Box::new(rhs),
-                    },
-                    ty: Type::Null,
-                }),
+                Expr::Get { object, name } => {
+                    self.check_field_assignment(&object.ty, &name, (&rhs.ty, rhs.span))?;
+
+                    Ok(Expression {
+                        span: Span::combine(&[expr.span, rhs.span]),
+                        expr: Expr::Set {
+                            object,
+                            name,
+                            rhs: Box::new(rhs),
+                        },
+                        ty: Type::Null,
+                    })
+                }
                 Expr::IndexGet { lhs, index } => Ok(Expression {
                     span: Span::combine(&[expr.span, rhs.span]),
                     expr: Expr::IndexSet {
@@ -701,6 +2770,35 @@ impl Parser {
         Ok(expr)
     }
 
+    // 'x |> f(a)' is parsed as 'f(x, a)', and 'x |> f' as 'f(x)'
+    fn pipe(&mut self) -> Result<Expression, ParseError> {
+        trace!("parse_pipe");
+        let mut expr = self.or()?;
+
+        while self.at(TokenKind::Pipe) {
+            self.bump()?;
+            let lhs_span = expr.span;
+
+            let mut piped = Some(expr);
+            let rhs = self.call(&mut piped)?;
+
+            expr = match piped {
+                Some(piped) => {
+                    let paren = rhs.span;
+                    let args = vec![Arg {
+                        name: None,
+                        value: piped,
+                    }];
+                    self.make_call(rhs, paren, args)?
+                }
+                None => rhs,
+            };
+            expr.span = Span::combine(&[lhs_span, expr.span]);
+        }
+
+        Ok(expr)
+    }
+
     fn or(&mut self) -> Result<Expression, ParseError> {
         trace!("parse_or");
         let mut expr = self.and()?;
@@ -770,13 +2868,55 @@ impl Parser {
             };
         }
 
-        Ok(expr)
-    }
-
-    fn comparison(&mut self) -> Result<Expression, ParseError> {
-        trace!("parse_comparison");
-        let mut expr = self.term()?;
-
+        Ok(expr)
+    }
+
+    fn comparison(&mut self) -> Result<Expression, ParseError> {
+        trace!("parse_comparison");
+        let mut expr = self.term()?;
+
+        if self.at_set(&[TokenKind::Is, TokenKind::As]) {
+            let is = self.bump()?.kind == TokenKind::Is;
+            let ty = self.ty()?;
+
+            // The value has to be able to have the type, for example 'x as int' where x is of
+            // type 'int | string'
+            // Enum members can also be cast to their value, Ex: Status.Ok as int
+            let enum_value = !is && matches!((&expr.ty, &ty.ty), (Type::Enum(..), Type::Int));
+            if !type_compatible(&expr.ty, &ty.ty)
+                && !type_compatible(&ty.ty, &expr.ty)
+                && !enum_value
+            {
+                return Err(ParseError::ImpossibleCast(
+                    self.source.id,
+                    Span::combine(&[expr.span, ty.span]).into(),
+                    ty.ty,
+                    expr.ty,
+                ));
+            }
+
+            let span = Span::combine(&[expr.span, ty.span]);
+            expr = if is {
+                Expression {
+                    span,
+                    ty: Type::Bool,
+                    expr: Expr::Is {
+                        expr: Box::new(expr),
+                        ty,
+                    },
+                }
+            } else {
+                Expression {
+                    span,
+                    ty: ty.ty.clone(),
+                    expr: Expr::As {
+                        expr: Box::new(expr),
+                        ty,
+                    },
+                }
+            };
+        }
+
         while self.at_set(&[
             TokenKind::Greater,
             TokenKind::GreaterEqual,
@@ -889,17 +3029,28 @@ impl Parser {
 
     fn index(&mut self) -> Result<Expression, ParseError> {
         trace!("parse_index");
-        let mut expr = self.call()?;
+        let mut expr = self.call(&mut None)?;
         if self.at(TokenKind::LeftBracket) {
             self.bump()?;
             let index_expr = self.expr()?;
             let end_span = self.expect(TokenKind::RightBracket)?.span;
 
-            // TODO type checking for indices
+            // TODO type checking for list indices
+            let mut ty = Type::Any;
+            if let Type::Instance(class_name) = &expr.ty {
+                let Some(method) = self.class_method(class_name, INDEX_OVERLOAD_METHOD) else {
+                    return Err(ParseError::NotIndexable(
+                        self.source.id,
+                        expr.span.into(),
+                        expr.ty.clone(),
+                    ));
+                };
+                ty = self.overload_type(method, &index_expr)?;
+            }
 
             expr = Expression {
                 span: Span::combine(&[expr.span, end_span]),
-                ty: Type::Any,
+                ty,
                 expr: Expr::IndexGet {
                     lhs: Box::new(expr),
                     index: Box::new(index_expr),
@@ -910,20 +3061,31 @@ impl Parser {
         Ok(expr)
     }
 
-    fn call(&mut self) -> Result<Expression, ParseError> {
+    // The call that ends the chain takes the piped value, if any, as its first argument. Ex: in
+    // '5 |> make().add(1)' it goes to 'add'
+    fn call(&mut self, piped: &mut Option<Expression>) -> Result<Expression, ParseError> {
         trace!("parse_call");
         let mut expr = self.primary()?;
 
         loop {
             if self.at(TokenKind::LeftParen) {
                 self.bump()?;
-                expr = self.finish_call(expr)?;
+                expr = self.finish_call(expr, piped)?;
             } else if self.at(TokenKind::Dot) {
                 self.bump()?;
-                let name = self.expect(TokenKind::Ident)?;
+                if self.at(TokenKind::Int) || self.at(TokenKind::Float) {
+                    expr = self.tuple_get(expr)?;
+                    continue;
+                }
+
+                let name: Spanned<String> = self.expect(TokenKind::Ident)?.into();
 
-                // Can only use dot operator when left hand side is of type Instance or Class
-                if !matches!(expr.ty, Type::Any | Type::Instance(..) | Type::Class(..)) {
+                // Can only use dot operator when left hand side is of type Instance, Class or
+                // Generator
+                if !matches!(
+                    expr.ty,
+                    Type::Any | Type::Instance(..) | Type::Class(..) | Type::Generator(..)
+                ) {
                     return Err(ParseError::OnlyInstancesAndClassesHaveProperties(
                         self.source.id,
                         expr.span.into(),
@@ -931,11 +3093,14 @@ impl Parser {
                     ));
                 }
 
+                let assigned = self.at(TokenKind::Equal);
+                let ty = self.property_type(&expr.ty, &name, assigned)?;
+
                 expr = Expression {
                     span: Span::combine(&[expr.span, name.span]),
-                    ty: Type::Any,
+                    ty,
                     expr: Expr::Get {
-                        name: name.into(),
+                        name,
                         object: Box::new(expr),
                     },
                 };
@@ -947,14 +3112,84 @@ impl Parser {
         Ok(expr)
     }
 
-    fn finish_call(&mut self, callee: Expression) -> Result<Expression, ParseError> {
+    fn tuple_get(&mut self, tuple: Expression) -> Result<Expression, ParseError> {
+        trace!("parse_tuple_get");
+        let token = self.bump()?.clone();
+
+        // 'pair.0.1' lexes the indices as the float '0.1', so split it back up
+        let mut expr = tuple;
+        let mut start = token.span.start;
+        for index in token.text.split('.') {
+            let span = Span::new(token.span.source_id, start, start + index.len());
+            start += index.len() + 1;
+
+            let elems = match &expr.ty {
+                Type::Tuple(elems) => Some(elems),
+                Type::Any => None,
+                ty => {
+                    return Err(ParseError::NotATuple(
+                        self.source.id,
+                        expr.span.into(),
+                        ty.clone(),
+                    ))
+                }
+            };
+
+            let index: usize = index.parse().unwrap_or(usize::MAX);
+            let ty = match elems {
+                Some(elems) => match elems.get(index) {
+                    Some(elem) => elem.ty.clone(),
+                    None => {
+                        return Err(ParseError::TupleIndexOutOfBounds(
+                            self.source.id,
+                            span.into(),
+                            expr.ty.clone(),
+                            index,
+                        ))
+                    }
+                },
+                None => Type::Any,
+            };
+
+            expr = Expression {
+                span: Span::combine(&[expr.span, span]),
+                ty,
+                expr: Expr::TupleGet {
+                    tuple: Box::new(expr),
+                    index: Spanned { item: index, span },
+                },
+            };
+        }
+
+        Ok(expr)
+    }
+
+    fn finish_call(
+        &mut self,
+        callee: Expression,
+        piped: &mut Option<Expression>,
+    ) -> Result<Expression, ParseError> {
         trace!("parse_finish_call");
-        let mut args: Vec<Expression> = Vec::new();
+        let mut args: Vec<Arg> = Vec::new();
 
         // Check if we have args
         if !self.at(TokenKind::RightParen) {
             loop {
-                args.push(self.expr()?);
+                let mut name = None;
+                if self.at(TokenKind::Ident)
+                    && self.source.peek_nth_kind(1) == Some(TokenKind::Colon)
+                {
+                    name = Some(self.bump()?.into());
+                    self.bump()?;
+                }
+
+                let value = if name.is_none() && self.at(TokenKind::Ellipsis) {
+                    self.spread()?
+                } else {
+                    self.expr()?
+                };
+
+                args.push(Arg { name, value });
 
                 if self.at(TokenKind::Comma) {
                     self.bump()?;
@@ -966,12 +3201,42 @@ impl Parser {
 
         let paren = self.expect(TokenKind::RightParen)?.span;
 
-        trace!(format!("{:#?}", callee.expr));
+        if !self.at(TokenKind::LeftParen) && !self.at(TokenKind::Dot) {
+            if let Some(piped) = piped.take() {
+                args.insert(
+                    0,
+                    Arg {
+                        name: None,
+                        value: piped,
+                    },
+                );
+            }
+        }
 
-        // TODO: type checking on get expr's for instances
-        // Example: someInstance.foo()
+        self.make_call(callee, paren, args)
+    }
+
+    fn make_call(
+        &mut self,
+        callee: Expression,
+        paren: Span,
+        args: Vec<Arg>,
+    ) -> Result<Expression, ParseError> {
+        trace!(format!("{:#?}", callee.expr));
 
         self.is_callable(&callee)?;
+        if let Expr::Get { object, name } = &callee.expr {
+            if object.ty == Type::Any {
+                self.untyped_method_calls.push(name.clone());
+            }
+        }
+        let callee = self.resolve_overload(callee, &args)?;
+
+        if let Expr::Variable(name) = &callee.expr {
+            if self.inferring.as_ref() == Some(name) {
+                self.recursive_call = Some(callee.span);
+            }
+        }
 
         let ty = match &callee.ty {
             Type::Class(class_name) => {
@@ -981,20 +3246,32 @@ impl Parser {
                     .lookup(class_name)
                     .expect("ICE: parser should have checked if class was declared by this point");
                 if let Sym::Class { methods, .. } = &entry.sym {
-                    if let Some(constructor) = methods.get("constructor") {
-                        if let Type::Function { params, .. } = &constructor.ty {
-                            for (param, arg) in params.iter().zip(args.iter()) {
-                                if !type_compatible(&param.ty, &arg.ty) {
-                                    return Err(ParseError::IncompatibleTypes(
-                                        callee.span.source_id,
-                                        param.span.into(),
-                                        param.ty.clone(),
-                                        arg.span.into(),
-                                        arg.ty.clone(),
-                                    ));
-                                }
-                            }
+                    match methods.get("constructor") {
+                        Some(
+                            constructor @ Symbol {
+                                ty:
+                                    Type::Function {
+                                        params, variadic, ..
+                                    },
+                                ..
+                            },
+                        ) => {
+                            self.check_visible(
+                                class_name,
+                                constructor,
+                                &Spanned {
+                                    item: constructor.name.item.clone(),
+                                    span: callee.span,
+                                },
+                            )?;
+                            self.check_args(&callee, Some(constructor), params, *variadic, &args)?
+                        }
+                        // Classes without constructors take no arguments, but while the class is
+                        // being declared its constructor might not have been parsed yet
+                        _ if self.current_class.as_ref() != Some(class_name) => {
+                            self.check_args(&callee, None, &[], false, &args)?
                         }
+                        _ => {}
                     }
 
                     Type::Instance(class_name.to_string())
@@ -1002,7 +3279,14 @@ impl Parser {
                     panic!("ICE: callee symtab type and actual type are not the same");
                 }
             }
-            Type::Function { returns, .. } => returns.ty.clone(),
+            Type::Function {
+                params,
+                returns,
+                variadic,
+            } => {
+                self.check_args(&callee, self.callee_decl(&callee), params, *variadic, &args)?;
+                returns.ty.clone()
+            }
             _ => callee.ty.clone(),
         };
 
@@ -1017,6 +3301,158 @@ impl Parser {
         })
     }
 
+    fn param(&mut self, previous: Option<&Param>) -> Result<Param, ParseError> {
+        trace!("parse_param");
+        let rest = self.at(TokenKind::Ellipsis);
+        if rest {
+            self.bump()?;
+        }
+
+        let mut param: Param = self.binding()?.into();
+
+        if rest {
+            // Rest params are always lists, and can't have a default since they default to []
+            param.rest = true;
+            param.ty = match param.ty {
+                Type::Any => Type::List(Box::new(TypeExpression::any())),
+                ty @ Type::List(..) => ty,
+                _ => {
+                    return Err(ParseError::InvalidRestParameter(
+                        self.source.id,
+                        param.name.span.into(),
+                    ))
+                }
+            };
+
+            if self.at(TokenKind::Equal) {
+                return Err(ParseError::InvalidRestParameter(
+                    self.source.id,
+                    param.name.span.into(),
+                ));
+            }
+        } else if self.at(TokenKind::Equal) {
+            self.bump()?;
+            let default = self.expr()?;
+            if !type_compatible(&param.ty, &default.ty) {
+                return Err(ParseError::IncompatibleTypes(
+                    self.source.id,
+                    param.name.span.into(),
+                    param.ty,
+                    default.span.into(),
+                    default.ty,
+                ));
+            }
+
+            // Unannotated params take the type of their default value
+            if param.ty == Type::Any {
+                param.ty = default.ty.clone();
+            }
+
+            param.default = Some(default);
+        } else if previous.is_some_and(|previous| previous.default.is_some()) {
+            return Err(ParseError::MissingDefaultValue(
+                self.source.id,
+                param.name.span.into(),
+                param.name.item,
+            ));
+        }
+
+        Ok(param)
+    }
+
+    // Number of tokens before the 'for' of a list comprehension, if the list that is being parsed
+    // is one
+    fn comprehension_for(&mut self) -> Option<usize> {
+        let mut depth = 0;
+        let mut n = 0;
+        loop {
+            match self.source.peek_nth_kind(n)? {
+                TokenKind::LeftParen | TokenKind::LeftBracket | TokenKind::LeftBrace => depth += 1,
+                TokenKind::RightParen | TokenKind::RightBracket | TokenKind::RightBrace => {
+                    if depth == 0 {
+                        return None;
+                    }
+                    depth -= 1;
+                }
+                TokenKind::For if depth == 0 => return Some(n),
+                TokenKind::Semicolon if depth == 0 => return None,
+                _ => {}
+            }
+            n += 1;
+        }
+    }
+
+    fn list_comprehension(
+        &mut self,
+        start_span: Span,
+        for_offset: usize,
+    ) -> Result<Expression, ParseError> {
+        trace!("parse_list_comprehension");
+
+        // The value uses the loop variable, so it's parsed after the rest of the comprehension
+        let value_start = self.source.checkpoint();
+        for _ in 0..for_offset {
+            self.bump()?;
+        }
+
+        self.expect(TokenKind::For)?;
+        self.symtab.level_up();
+        let binding = self.binding()?;
+        self.expect(TokenKind::In)?;
+        let iterable = self.expr()?;
+        self.declare_loop_variable(&binding, &iterable)?;
+
+        let mut cond = None;
+        if self.at(TokenKind::If) {
+            self.bump()?;
+            cond = Some(Box::new(self.expr()?));
+        }
+
+        let end_span = self.expect(TokenKind::RightBracket)?.span;
+        let end = self.source.checkpoint();
+
+        self.source.restore(value_start);
+        let value = self.expr()?;
+        self.expect(TokenKind::For)?;
+        self.source.restore(end);
+
+        self.symtab.level_down();
+
+        Ok(Expression {
+            ty: Type::List(Box::new(TypeExpression {
+                ty: value.ty.clone(),
+                span: value.span,
+            })),
+            expr: Expr::ListComprehension {
+                value: Box::new(value),
+                binding,
+                iterable: Box::new(iterable),
+                cond,
+            },
+            span: Span::combine(&[start_span, end_span]),
+        })
+    }
+
+    fn spread(&mut self) -> Result<Expression, ParseError> {
+        trace!("parse_spread");
+        let start_span = self.expect(TokenKind::Ellipsis)?.span;
+        let list = self.expr()?;
+
+        if !matches!(list.ty, Type::List(..) | Type::Any) {
+            return Err(ParseError::InvalidSpread(
+                self.source.id,
+                list.span.into(),
+                list.ty,
+            ));
+        }
+
+        Ok(Expression {
+            span: Span::combine(&[start_span, list.span]),
+            ty: list.ty.clone(),
+            expr: Expr::Spread(Box::new(list)),
+        })
+    }
+
     fn binding(&mut self) -> Result<Binding, ParseError> {
         trace!("parse_binding");
         let ident = self.expect(TokenKind::Ident)?;
@@ -1024,7 +3460,10 @@ impl Parser {
         let name = ident.text.to_string();
         let span = ident.span;
 
-        if let Some(sym) = self.symtab.lookup(&name) {
+        // Variables in nested scopes can shadow outer variables, which is only a lint warning
+        if let Some(sym) = self.symtab.lookup(&name).filter(|sym| {
+            sym.sym != Sym::Variable || self.symtab.lookup_local(&sym.name.item).is_some()
+        }) {
             return Err(ParseError::CannotRedeclareSymbol(
                 self.source.id,
                 sym.name.item.clone(),
@@ -1048,6 +3487,49 @@ impl Parser {
 
     fn ty(&mut self) -> Result<TypeExpression, ParseError> {
         trace!("parse_ty");
+        let first = self.single_ty()?;
+        if !self.at(TokenKind::Bar) {
+            return Ok(first);
+        }
+
+        // Union type. Ex: int | string | null
+        let mut span = first.span;
+        let mut tys: Vec<TypeExpression> = Vec::new();
+        let mut next = Some(first);
+        while let Some(ty) = next {
+            span = Span::combine(&[span, ty.span]);
+
+            // Nested unions, like from aliases, are flattened
+            let members = match ty.ty {
+                Type::Union(members) => members,
+                _ => vec![ty],
+            };
+            for member in members {
+                if !tys.iter().any(|ty| ty.ty == member.ty) {
+                    tys.push(member);
+                }
+            }
+
+            next = if self.at(TokenKind::Bar) {
+                self.bump()?;
+                Some(self.single_ty()?)
+            } else {
+                None
+            };
+        }
+
+        if tys.len() == 1 {
+            return Ok(tys.remove(0));
+        }
+
+        Ok(TypeExpression {
+            ty: Type::Union(tys),
+            span,
+        })
+    }
+
+    fn single_ty(&mut self) -> Result<TypeExpression, ParseError> {
+        trace!("parse_single_ty");
         let token = self.bump()?;
         let span = token.span;
 
@@ -1058,7 +3540,21 @@ impl Parser {
             TokenKind::TypeString => Type::String,
             TokenKind::Null => Type::Null,
             TokenKind::TypeAny => Type::Any,
-            TokenKind::Ident => Type::Instance(token.text.clone()),
+            TokenKind::Ident => {
+                let name = token.text.clone();
+                match self.symtab.lookup(&name) {
+                    Some(Symbol {
+                        sym: Sym::TypeAlias,
+                        ty,
+                        ..
+                    }) => ty.clone(),
+                    Some(Symbol {
+                        sym: Sym::Enum { .. },
+                        ..
+                    }) => Type::Enum(name),
+                    _ => Type::Instance(name),
+                }
+            }
             TokenKind::LeftBracket => {
                 // array types. Ex: [int]
                 let list_ty = self.ty()?;
@@ -1069,22 +3565,53 @@ impl Parser {
                 });
             }
             TokenKind::LeftParen => {
-                // function type. Ex: (int, int) -> int
+                // function type. Ex: (int, int) -> int, or (string, ...[int]) -> int
+                // or tuple type. Ex: (int, string), or (int,)
                 let mut params = Vec::new();
+                let mut variadic = false;
+                let mut trailing_comma = false;
                 if !self.at(TokenKind::RightParen) {
                     loop {
+                        if self.at(TokenKind::Ellipsis) {
+                            self.bump()?;
+                            variadic = true;
+                        }
+
                         let param = self.ty()?;
+                        if variadic && !matches!(param.ty, Type::List(..)) {
+                            return Err(ParseError::InvalidRestParameter(
+                                self.source.id,
+                                param.span.into(),
+                            ));
+                        }
                         params.push(param);
 
-                        if self.at(TokenKind::Comma) {
+                        // The rest param has to be last
+                        if self.at(TokenKind::Comma) && !variadic {
                             self.bump()?;
+                            if self.at(TokenKind::RightParen) {
+                                trailing_comma = true;
+                                break;
+                            }
                         } else {
                             break;
                         }
                     }
                 }
 
-                self.expect(TokenKind::RightParen)?;
+                let right_paren = self.expect(TokenKind::RightParen)?.span;
+
+                if !self.at(TokenKind::Arrow) && !params.is_empty() && !variadic {
+                    // A single type without a trailing comma is just parenthesized
+                    if params.len() == 1 && !trailing_comma {
+                        return Ok(params.remove(0));
+                    }
+
+                    return Ok(TypeExpression {
+                        ty: Type::Tuple(params),
+                        span: Span::combine(&[span, right_paren]),
+                    });
+                }
 
                 // Function types must define return type
                 self.expect(TokenKind::Arrow)?;
@@ -1095,6 +3622,7 @@ impl Parser {
                     ty: Type::Function {
                         params,
                         returns: Box::new(returns),
+                        variadic,
                     },
                 });
             }
@@ -1111,6 +3639,8 @@ impl Parser {
         let token_span = token.span;
 
         match token.kind {
+            TokenKind::If => self.if_expr(token_span, true),
+            TokenKind::LeftBrace => self.block_expr(token_span),
             TokenKind::False => Ok(Expression {
                 expr: Expr::Bool(false),
                 span: token.span,
@@ -1126,15 +3656,58 @@ impl Parser {
                 span: token.span,
                 ty: Type::Null,
             }),
-            TokenKind::This => Ok(Expression {
-                expr: Expr::This,
-                span: token_span,
-                ty: Type::Any,
-            }),
+            TokenKind::This => {
+                if self.in_static {
+                    return Err(ParseError::ThisInStaticMethod(
+                        self.source.id,
+                        token_span.into(),
+                    ));
+                }
+
+                if self.in_field_initializer {
+                    return Err(ParseError::ThisInFieldInitializer(
+                        self.source.id,
+                        token_span.into(),
+                    ));
+                }
+
+                Ok(Expression {
+                    expr: Expr::This,
+                    span: token_span,
+                    ty: self.current_class.clone().map_or(Type::Any, Type::Instance),
+                })
+            }
             TokenKind::LeftParen => {
                 let span = token.span;
                 let expr = self.expr()?;
 
+                // A comma makes it a tuple instead of a grouping. Ex: (1, "a"), or (1,)
+                if self.at(TokenKind::Comma) {
+                    let mut elems = vec![expr];
+                    while self.at(TokenKind::Comma) {
+                        self.bump()?;
+                        if self.at(TokenKind::RightParen) {
+                            break;
+                        }
+                        elems.push(self.expr()?);
+                    }
+
+                    let right_paren = self.expect(TokenKind::RightParen)?;
+                    return Ok(Expression {
+                        ty: Type::Tuple(
+                            elems
+                                .iter()
+                                .map(|elem| TypeExpression {
+                                    ty: elem.ty.clone(),
+                                    span: elem.span,
+                                })
+                                .collect(),
+                        ),
+                        expr: Expr::Tuple(elems),
+                        span: Span::combine(&[span, right_paren.span]),
+                    });
+                }
+
                 let right_paren = self.expect(TokenKind::RightParen)?;
                 Ok(Expression {
                     ty: expr.ty.clone(),
@@ -1145,18 +3718,25 @@ impl Parser {
             TokenKind::Ident => {
                 let ident = token.text.to_string();
                 let span = token.span;
-                if let Some(symbol) = self.symtab.lookup(&ident) {
-                    Ok(Expression {
+                match self.symtab.lookup(&ident) {
+                    Some(Symbol {
+                        sym: Sym::TypeAlias,
+                        ..
+                    }) => Err(ParseError::TypeAliasAsValue(
+                        self.source.id,
+                        span.into(),
+                        ident,
+                    )),
+                    Some(symbol) => Ok(Expression {
                         expr: Expr::Variable(ident),
                         span,
                         ty: symbol.ty.clone(),
-                    })
-                } else {
-                    Err(ParseError::UndeclaredVariable(
+                    }),
+                    None => Err(ParseError::UndeclaredVariable(
                         self.source.id,
                         span.into(),
                         ident,
-                    ))
+                    )),
                 }
             }
             TokenKind::Int => {
@@ -1195,10 +3775,25 @@ impl Parser {
             }
             TokenKind::LeftBracket => {
                 let start_span = token.span;
+                let declared_element = self.list_element.take();
+
+                if let Some(for_offset) = self.comprehension_for() {
+                    return self.list_comprehension(start_span, for_offset);
+                }
 
                 let mut exprs = vec![];
                 if !self.at(TokenKind::RightBracket) {
                     loop {
+                        if self.at(TokenKind::Ellipsis) {
+                            exprs.push(self.spread()?);
+                            if self.at(TokenKind::Comma) {
+                                self.bump()?;
+                                continue;
+                            } else {
+                                break;
+                            }
+                        }
+
                         let expr = self.expr()?;
 
                         // It's actually list shorthand expression
@@ -1245,18 +3840,29 @@ impl Parser {
                     ty: Type::Any,
                     span: Span::combine(&[start_span, end_span]),
                 };
+                let declared = declared_element.is_some();
+                if let Some(element) = declared_element {
+                    list_ty = element;
+                }
+
                 for expr in &exprs {
-                    if !type_compatible(&list_ty.ty, &expr.ty) {
+                    // Spread lists contribute their elements
+                    let ty = match &expr.expr {
+                        Expr::Spread(..) => element_type(&expr.ty),
+                        _ => expr.ty.clone(),
+                    };
+
+                    if !type_compatible(&list_ty.ty, &ty) {
                         return Err(ParseError::IncompatibleTypes(
                             expr.span.source_id,
                             list_ty.span.into(),
                             list_ty.ty,
                             expr.span.into(),
-                            expr.ty.clone(),
+                            ty,
                         ));
-                    } else {
+                    } else if !declared {
                         // coerce list type
-                        list_ty.ty = expr.ty.clone();
+                        list_ty.ty = ty;
                     }
                 }
 
@@ -1273,3 +3879,248 @@ impl Parser {
         }
     }
 }
+
+fn function_sym(params: &[Param]) -> Sym {
+    Sym::Function {
+        arity: params.len(),
+        required: params
+            .iter()
+            .take_while(|param| param.default.is_none() && !param.rest)
+            .count(),
+        params: params.iter().map(|param| param.name.item.clone()).collect(),
+        overloads: vec![],
+    }
+}
+
+// Builds 'name(member) -> string' and 'values() -> [Enum]' for an enum with the given members
+// Names of the functions every enum gets, see enum_api
+const ENUM_API: [&str; 2] = ["name", "values"];
+
+fn enum_api(name: &Spanned<String>, members: &[(Spanned<String>, i64)]) -> Vec<Function> {
+    let span = name.span;
+    let ty = TypeExpression {
+        ty: Type::Enum(name.item.clone()),
+        span,
+    };
+    let expr = |expr: Expr, ty: Type| Expression { expr, span, ty };
+    let stmt = |stmt: Stmt| Statement { stmt, span };
+    let member = |value: i64| expr(Expr::Int(value), ty.ty.clone());
+
+    // if member == 200 { ret "Ok"; } ...
+    let mut name_body = members
+        .iter()
+        .map(|(member_name, value)| {
+            stmt(Stmt::If {
+                cond: expr(
+                    Expr::Binary {
+                        lhs: Box::new(expr(Expr::Variable(String::from("member")), ty.ty.clone())),
+                        op: Operator {
+                            op: Op::Equals,
+                            span,
+                        },
+                        rhs: Box::new(member(*value)),
+                    },
+                    Type::Bool,
+                ),
+                body: Box::new(stmt(Stmt::Block(vec![stmt(Stmt::Return(Some(expr(
+                    Expr::String(member_name.item.clone()),
+                    Type::String,
+                ))))]))),
+                else_branch: None,
+            })
+        })
+        .collect::<Vec<_>>();
+    name_body.push(stmt(Stmt::Return(None)));
+
+    let values_ty = Type::List(Box::new(ty.clone()));
+    let values = expr(
+        Expr::List(members.iter().map(|(_, value)| member(*value)).collect()),
+        values_ty.clone(),
+    );
+
+    let function = |item: &str, params: Vec<Param>, body: Vec<Statement>, returns: Type| {
+        let func_ty = Type::Function {
+            params: params.iter().cloned().map(TypeExpression::from).collect(),
+            returns: Box::new(TypeExpression { ty: returns, span }),
+            variadic: false,
+        };
+
+        Function {
+            name: Spanned {
+                item: item.to_string(),
+                span,
+            },
+            params,
+            body: Box::new(stmt(Stmt::Block(body))),
+            ty: TypeExpression { ty: func_ty, span },
+            generator: false,
+        }
+    };
+
+    vec![
+        function(
+            ENUM_API[0],
+            vec![Param {
+                name: Spanned {
+                    item: String::from("member"),
+                    span,
+                },
+                ty: ty.ty.clone(),
+                default: None,
+                rest: false,
+            }],
+            name_body,
+            Type::String,
+        ),
+        function(
+            ENUM_API[1],
+            vec![],
+            vec![stmt(Stmt::Return(Some(values)))],
+            values_ty,
+        ),
+    ]
+}
+
+// Adds an overload to a function, unless the function or one of its overloads already has the
+// same param types, since calls couldn't tell them apart. Returns the name it is declared under.
+fn add_overload(
+    id: SourceId,
+    primary: &mut Symbol,
+    mut symbol: Symbol,
+) -> Result<String, ParseError> {
+    let Sym::Function { overloads, .. } = &mut primary.sym else {
+        panic!("ICE: overloaded a symbol that isn't a function");
+    };
+
+    let declared = std::iter::once(&primary.name)
+        .chain(overloads.iter().map(|overload| &overload.name))
+        .zip(std::iter::once(&primary.ty).chain(overloads.iter().map(|overload| &overload.ty)))
+        .find(|(_, ty)| same_params(ty, &symbol.ty));
+    if let Some((declared, _)) = declared {
+        return Err(ParseError::CannotRedeclareSymbol(
+            id,
+            symbol.name.item,
+            symbol.name.span.into(),
+            declared.span.into(),
+        ));
+    }
+
+    let name = overload_name(&symbol.name.item, &symbol.ty);
+    symbol.name.item = name.clone();
+    overloads.push(symbol);
+
+    Ok(name)
+}
+
+// Where the type mentions instances of the given class, if it does
+fn instance_span(ty: &TypeExpression, class_name: &str) -> Option<Span> {
+    match &ty.ty {
+        Type::Instance(name) if name == class_name => Some(ty.span),
+        Type::List(inner) | Type::Generator(inner) => instance_span(inner, class_name),
+        Type::Tuple(types) | Type::Union(types) => {
+            types.iter().find_map(|ty| instance_span(ty, class_name))
+        }
+        Type::Function {
+            params, returns, ..
+        } => params
+            .iter()
+            .chain(std::iter::once(returns.as_ref()))
+            .find_map(|ty| instance_span(ty, class_name)),
+        _ => None,
+    }
+}
+
+// The type of a value that can come from any of the given types. Each distinct type is only
+// kept once, and anything that can be 'any' is 'any'
+fn unify_types(types: Vec<TypeExpression>) -> Type {
+    let mut unified: Vec<TypeExpression> = Vec::new();
+    for ty in types {
+        let members = match ty.ty {
+            Type::Union(members) => members,
+            _ => vec![ty],
+        };
+        for member in members {
+            if !unified.iter().any(|ty| ty.ty == member.ty) {
+                unified.push(member);
+            }
+        }
+    }
+
+    if unified.iter().any(|ty| ty.ty == Type::Any) {
+        return Type::Any;
+    }
+
+    match unified.len() {
+        0 => Type::Null,
+        1 => unified.remove(0).ty,
+        _ => Type::Union(unified),
+    }
+}
+
+fn same_params(lhs: &Type, rhs: &Type) -> bool {
+    match (lhs, rhs) {
+        (Type::Function { params: lhs, .. }, Type::Function { params: rhs, .. }) => {
+            lhs.len() == rhs.len() && lhs.iter().zip(rhs).all(|(lhs, rhs)| lhs.ty == rhs.ty)
+        }
+        _ => false,
+    }
+}
+
+// Ex: area(float, float)
+fn overload_name(name: &str, ty: &Type) -> String {
+    let params = match ty {
+        Type::Function { params, .. } => params
+            .iter()
+            .map(|param| format!("{}", param.ty))
+            .collect::<Vec<_>>(),
+        _ => vec![],
+    };
+
+    format!("{}({})", name, params.join(", "))
+}
+
+// Overloads are stored on the first declaration of a function, so 'area(float)' is found on 'area'
+fn lookup_overloaded<'a>(
+    name: &str,
+    lookup: impl Fn(&str) -> Option<&'a Symbol>,
+) -> Option<&'a Symbol> {
+    match name.split_once('(') {
+        Some((base, _)) => lookup(base)?.overload(name),
+        None => lookup(name),
+    }
+}
+
+// Methods the interpreter calls by name can't be overloaded
+fn overloadable_method(name: &str) -> bool {
+    !(name == "constructor"
+        || name.starts_with("op_")
+        || [TO_STRING_METHOD, EQUALS_METHOD, HASH_METHOD].contains(&name))
+}
+
+// How many of the arguments have exactly the type of the param they are passed to
+fn exact_matches(decl: &Symbol, params: &[TypeExpression], args: &[Arg]) -> usize {
+    let names = match &decl.sym {
+        Sym::Function { params, .. } => params.as_slice(),
+        _ => &[],
+    };
+
+    args.iter()
+        .enumerate()
+        .filter(|(pos, arg)| {
+            let idx = match &arg.name {
+                Some(name) => names.iter().position(|param| *param == name.item),
+                None => Some(*pos),
+            };
+
+            idx.and_then(|idx| params.get(idx))
+                .is_some_and(|param| param.ty == arg.value.ty)
+        })
+        .count()
+}
+
+fn element_type(ty: &Type) -> Type {
+    match ty {
+        Type::List(inner) => inner.ty.clone(),
+        _ => Type::Any,
+    }
+}
@@ -23,6 +23,12 @@ impl Source {
         }
     }
 
+    pub fn comments(&self) -> impl Iterator<Item = &Token> {
+        self.tokens
+            .iter()
+            .filter(|token| token.kind == TokenKind::Comment)
+    }
+
     pub fn next_token(&mut self) -> Option<&Token> {
         self.eat_trivia();
 
@@ -37,6 +43,30 @@ impl Source {
         self.peek_kind_raw()
     }
 
+    // Peeks past the next token, skipping trivia. peek_nth_kind(0) is the same as peek_kind()
+    pub fn peek_nth_kind(&mut self, n: usize) -> Option<TokenKind> {
+        self.eat_trivia();
+        self.tokens[self.cursor..]
+            .iter()
+            .filter(|token| !token.kind.is_trivia())
+            .nth(n)
+            .map(|token| token.kind)
+    }
+
+    pub fn peek_text(&mut self) -> Option<&str> {
+        self.eat_trivia();
+        self.peek_token_raw().map(|token| token.text.as_str())
+    }
+
+    // Saves the position of the cursor, so that tokens can be parsed again after a restore
+    pub fn checkpoint(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn restore(&mut self, checkpoint: usize) {
+        self.cursor = checkpoint;
+    }
+
     pub fn eof(&self) -> SourceSpan {
         (self.raw.len() - 1, 0).into()
     }
@@ -53,7 +83,7 @@ impl Source {
     }
 
     fn at_trivia(&self) -> bool {
-        self.peek_kind_raw().map_or(false, TokenKind::is_trivia)
+        self.peek_kind_raw().is_some_and(TokenKind::is_trivia)
     }
 
     fn peek_kind_raw(&self) -> Option<TokenKind> {
@@ -1,26 +1,69 @@
 use ast::ty::Type;
 use meta::Spanned;
-use std::{collections::HashMap, usize};
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Symbol {
     pub name: Spanned<String>,
     pub sym: Sym,
     pub ty: Type,
+    pub vis: Visibility,
+}
+
+// Class members are public unless marked 'priv'. Other symbols are only visible in their scope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    Public,
+    Private,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Sym {
     Variable,
+    // Parameters with default values always come after the 'required' ones. Declaring a function
+    // again with different parameter types overloads it, and the overloads are kept here on the
+    // first declaration under names like 'area(float)'
     Function {
         arity: usize,
+        required: usize,
+        params: Vec<String>,
+        overloads: Vec<Symbol>,
     },
     Class {
         methods: HashMap<String, Symbol>,
         statics: HashMap<String, Symbol>,
+        fields: HashMap<String, Symbol>,
+    },
+    // Enums only have statics, their members and methods
+    Enum {
+        statics: HashMap<String, Symbol>,
+    },
+    // The symbol's type is the aliased type
+    TypeAlias,
+    // A class field backed by a getter and/or a setter
+    Property {
+        get: bool,
+        set: bool,
     },
 }
 
+impl Symbol {
+    // Finds the overload of this function that is declared under the given name
+    pub fn overload(&self, name: &str) -> Option<&Symbol> {
+        match &self.sym {
+            Sym::Function { overloads, .. } => overloads.iter().find(|o| o.name.item == name),
+            _ => None,
+        }
+    }
+
+    pub fn overload_mut(&mut self, name: &str) -> Option<&mut Symbol> {
+        match &mut self.sym {
+            Sym::Function { overloads, .. } => overloads.iter_mut().find(|o| o.name.item == name),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct SymbolTable {
     inner: Vec<HashMap<String, Symbol>>,
@@ -71,6 +114,15 @@ impl SymbolTable {
         self.inner.pop();
     }
 
+    pub fn depth(&self) -> usize {
+        self.inner.len()
+    }
+
+    // Drops the scopes that were entered after the table had the given depth
+    pub fn truncate(&mut self, depth: usize) {
+        self.inner.truncate(depth);
+    }
+
     pub fn insert(&mut self, sym: Symbol) {
         if let Some(map) = self.inner.last_mut() {
             map.insert(sym.name.item.clone(), sym);
@@ -87,6 +139,15 @@ impl SymbolTable {
         None
     }
 
+    // Only looks in the innermost scope
+    pub fn lookup_local(&self, name: &str) -> Option<&Symbol> {
+        self.inner.last().and_then(|map| map.get(name))
+    }
+
+    pub fn lookup_local_mut(&mut self, name: &str) -> Option<&mut Symbol> {
+        self.inner.last_mut().and_then(|map| map.get_mut(name))
+    }
+
     pub fn lookup_mut(&mut self, name: &str) -> Option<&mut Symbol> {
         for map in self.inner.iter_mut().rev() {
             if let Some(entry) = map.get_mut(name) {
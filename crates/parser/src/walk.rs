@@ -0,0 +1,231 @@
+use ast::{
+    expr::{Expr, Expression},
+    stmt::{Class, Function, Statement, Stmt},
+};
+use meta::{Span, Spanned};
+
+pub(crate) enum Declaration {
+    Param,
+    Variable { initialized: bool },
+}
+
+// Walks the statements in order, keeping track of the variables in scope like the parser does.
+// Passes override the visit methods they care about, and call the matching walk function to keep
+// going into the children.
+pub(crate) trait Walker {
+    fn begin_scope(&mut self) {}
+
+    fn end_scope(&mut self) {}
+
+    fn declare(&mut self, _name: &Spanned<String>, _declaration: Declaration) {}
+
+    fn read(&mut self, _name: &str, _span: Span) {}
+
+    fn assign(&mut self, _name: &str) {}
+
+    fn visit_stmts(&mut self, stmts: &[Statement]) {
+        walk_stmts(self, stmts);
+    }
+
+    fn visit_function(&mut self, func: &Function) {
+        walk_function(self, func);
+    }
+
+    fn visit_stmt(&mut self, stmt: &Statement) {
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &Expression) {
+        walk_expr(self, expr);
+    }
+}
+
+pub(crate) fn walk_stmts<W: Walker + ?Sized>(walker: &mut W, stmts: &[Statement]) {
+    for stmt in stmts {
+        walker.visit_stmt(stmt);
+    }
+}
+
+// Blocks get their own scope, and so do the bodies of ifs and loops that aren't blocks
+pub(crate) fn walk_block<W: Walker + ?Sized>(walker: &mut W, stmt: &Statement) {
+    walker.begin_scope();
+    match &stmt.stmt {
+        Stmt::Block(stmts) => walker.visit_stmts(stmts),
+        _ => walker.visit_stmt(stmt),
+    }
+    walker.end_scope();
+}
+
+pub(crate) fn walk_function<W: Walker + ?Sized>(walker: &mut W, func: &Function) {
+    walker.begin_scope();
+    for param in &func.params {
+        if let Some(default) = &param.default {
+            walker.visit_expr(default);
+        }
+        walker.declare(&param.name, Declaration::Param);
+    }
+
+    // The params and the body share a scope
+    match &func.body.stmt {
+        Stmt::Block(stmts) => walker.visit_stmts(stmts),
+        _ => walker.visit_stmt(&func.body),
+    }
+    walker.end_scope();
+}
+
+// Static variables and fields are reached through the class, so they aren't variables
+fn walk_class<W: Walker + ?Sized>(walker: &mut W, class: &Class) {
+    let members = [
+        &class.methods,
+        &class.statics,
+        &class.fields,
+        &class.getters,
+        &class.setters,
+    ];
+
+    for member in members.into_iter().flatten() {
+        match &member.stmt {
+            Stmt::Function(func) => walker.visit_function(func),
+            Stmt::Variable {
+                expr: Some(expr), ..
+            } => walker.visit_expr(expr),
+            _ => {}
+        }
+    }
+}
+
+pub(crate) fn walk_stmt<W: Walker + ?Sized>(walker: &mut W, stmt: &Statement) {
+    match &stmt.stmt {
+        Stmt::Expr(expr) | Stmt::Yield(expr) | Stmt::Return(Some(expr)) => walker.visit_expr(expr),
+        Stmt::Return(None) | Stmt::TypeAlias { .. } => {}
+        Stmt::Function(func) => walker.visit_function(func),
+        Stmt::Class(class) => walk_class(walker, class),
+        Stmt::Struct(strukt) => strukt
+            .fields
+            .iter()
+            .filter_map(|field| field.default.as_ref())
+            .for_each(|default| walker.visit_expr(default)),
+        Stmt::Variable { name, expr } => {
+            if let Some(expr) = expr {
+                walker.visit_expr(expr);
+            }
+            let initialized = expr.is_some();
+            walker.declare(&name.name, Declaration::Variable { initialized });
+        }
+        Stmt::Destructure { pattern, expr } => {
+            walker.visit_expr(expr);
+            for binding in pattern.bindings() {
+                walker.declare(&binding.name, Declaration::Variable { initialized: true });
+            }
+        }
+        Stmt::Block(..) => walk_block(walker, stmt),
+        Stmt::If {
+            cond,
+            body,
+            else_branch,
+        } => {
+            walker.visit_expr(cond);
+            walk_block(walker, body);
+            if let Some(else_branch) = else_branch {
+                walk_block(walker, else_branch);
+            }
+        }
+        Stmt::Until { cond, body } => {
+            walker.visit_expr(cond);
+            walk_block(walker, body);
+        }
+        Stmt::For {
+            binding,
+            iterable,
+            body,
+        } => {
+            walker.visit_expr(iterable);
+            walker.begin_scope();
+            walker.declare(&binding.name, Declaration::Variable { initialized: true });
+            walk_block(walker, body);
+            walker.end_scope();
+        }
+    }
+}
+
+pub(crate) fn walk_expr<W: Walker + ?Sized>(walker: &mut W, expr: &Expression) {
+    match &expr.expr {
+        Expr::Bool(..)
+        | Expr::Int(..)
+        | Expr::Float(..)
+        | Expr::String(..)
+        | Expr::Null
+        | Expr::This => {}
+        Expr::Variable(name) => walker.read(name, expr.span),
+        Expr::Assign { name, rhs } => {
+            walker.visit_expr(rhs);
+            walker.assign(&name.item);
+        }
+        Expr::Unary { rhs: inner, .. }
+        | Expr::Grouping(inner)
+        | Expr::Get { object: inner, .. }
+        | Expr::TupleGet { tuple: inner, .. }
+        | Expr::Spread(inner)
+        | Expr::Is { expr: inner, .. }
+        | Expr::As { expr: inner, .. } => walker.visit_expr(inner),
+        Expr::Binary { lhs, rhs, .. }
+        | Expr::Logical { lhs, rhs, .. }
+        | Expr::Set {
+            object: lhs, rhs, ..
+        }
+        | Expr::IndexGet { lhs, index: rhs }
+        | Expr::ListShorthand {
+            value: lhs,
+            count: rhs,
+        } => {
+            walker.visit_expr(lhs);
+            walker.visit_expr(rhs);
+        }
+        Expr::IndexSet { lhs, index, rhs } => {
+            walker.visit_expr(lhs);
+            walker.visit_expr(index);
+            walker.visit_expr(rhs);
+        }
+        Expr::Call { callee, args, .. } => {
+            walker.visit_expr(callee);
+            args.iter().for_each(|arg| walker.visit_expr(&arg.value));
+        }
+        Expr::List(exprs) | Expr::Tuple(exprs) => {
+            exprs.iter().for_each(|expr| walker.visit_expr(expr))
+        }
+        Expr::ListComprehension {
+            value,
+            binding,
+            iterable,
+            cond,
+        } => {
+            walker.visit_expr(iterable);
+            walker.begin_scope();
+            walker.declare(&binding.name, Declaration::Variable { initialized: true });
+            if let Some(cond) = cond {
+                walker.visit_expr(cond);
+            }
+            walker.visit_expr(value);
+            walker.end_scope();
+        }
+        Expr::Block { stmts, value } => {
+            walker.begin_scope();
+            walker.visit_stmts(stmts);
+            if let Some(value) = value {
+                walker.visit_expr(value);
+            }
+            walker.end_scope();
+        }
+        Expr::If {
+            cond,
+            body,
+            else_branch,
+        } => {
+            walker.visit_expr(cond);
+            walker.visit_expr(body);
+            if let Some(else_branch) = else_branch {
+                walker.visit_expr(else_branch);
+            }
+        }
+    }
+}
@@ -1,42 +1,46 @@
 use ast::ty::Type;
 use interpreter::{env::Environment, error::RuntimeError, interpret, Builtin, Val, Value};
 use parser::{parse, source::Source, SymbolTable};
+use std::cell::RefCell;
 use wasm_bindgen::{prelude::*, JsValue};
 
-static mut OUTPUT: String = String::new();
+thread_local! {
+    static OUTPUT: RefCell<String> = const { RefCell::new(String::new()) };
+}
 
 #[wasm_bindgen]
 pub fn wasm_interpret(source: &str) -> JsValue {
-    unsafe {
-        OUTPUT = String::new();
-    }
+    OUTPUT.with_borrow_mut(String::clear);
 
-    match helper(source) {
-        Ok(_) => unsafe { JsValue::from_str(&format!("[Finished]\n{}", OUTPUT)) },
-        Err(e) => unsafe {
-            OUTPUT.push_str(&format!("{:?}", e));
-            JsValue::from_str(&OUTPUT)
-        },
-    }
+    let result = helper(source);
+    OUTPUT.with_borrow_mut(|output| match result {
+        Ok(_) => JsValue::from_str(&format!("[Finished]\n{}", output)),
+        Err(e) => {
+            output.push_str(&format!("{:?}", e));
+            JsValue::from_str(output)
+        }
+    })
 }
 
 pub fn helper(source: &str) -> miette::Result<()> {
     let mut builtins = vec![];
 
     fn print(vals: Vec<Value>, env: &mut Environment) -> Result<Value, RuntimeError> {
-        unsafe {
-            OUTPUT.push_str(
-                &vals.first()
-                    .expect("arity mismatch didn't catch builtin")
-                    .to_string(env)
-            );
-            OUTPUT.push('\n');
-        }
+        let line = vals
+            .iter()
+            .map(|val| val.to_string(env))
+            .collect::<Result<Vec<_>, _>>()?
+            .join(" ");
+        OUTPUT.with_borrow_mut(|output| {
+            output.push_str(&line);
+            output.push('\n');
+        });
         Ok(Value::null())
     }
-    builtins.push(Builtin::new(
+    builtins.push(Builtin::variadic(
         String::from("print"),
-        vec![Type::Any],
+        vec![],
+        Type::Any,
         None,
         print,
     ));